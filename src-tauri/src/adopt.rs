@@ -0,0 +1,234 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+use tauri::ipc::Channel;
+
+use crate::dedup;
+use crate::storage;
+use crate::tasks;
+
+#[derive(Clone, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ExistingInstall {
+    source: String,
+    path: String,
+    version: String,
+    is_latest: bool,
+}
+
+// Versions folders used by the official launcher and the common bootstrappers
+fn install_roots() -> Vec<(&'static str, PathBuf)> {
+    let mut roots = Vec::new();
+    if let Ok(local_app_data) = std::env::var("LOCALAPPDATA") {
+        let local_app_data = PathBuf::from(local_app_data);
+        roots.push(("Roblox", local_app_data.join("Roblox").join("Versions")));
+        roots.push((
+            "Bloxstrap",
+            local_app_data.join("Bloxstrap").join("Versions"),
+        ));
+        roots.push((
+            "Fishstrap",
+            local_app_data.join("Fishstrap").join("Versions"),
+        ));
+        roots.push((
+            "Voidstrap",
+            local_app_data.join("Voidstrap").join("Versions"),
+        ));
+    }
+    if let Ok(program_files_x86) = std::env::var("ProgramFiles(x86)") {
+        roots.push((
+            "Roblox",
+            PathBuf::from(program_files_x86)
+                .join("Roblox")
+                .join("Versions"),
+        ));
+    }
+    if let Ok(program_files) = std::env::var("ProgramFiles") {
+        roots.push((
+            "Roblox",
+            PathBuf::from(program_files).join("Roblox").join("Versions"),
+        ));
+    }
+    roots
+}
+
+fn discover(binary_type: &str) -> Vec<(&'static str, PathBuf)> {
+    let exe_name = crate::exe_name_for(binary_type);
+    let mut found = Vec::new();
+    for (source, root) in install_roots() {
+        if let Ok(entries) = fs::read_dir(&root) {
+            for entry in entries.flatten() {
+                let path = entry.path();
+                if path.is_dir() && path.join(exe_name).exists() {
+                    found.push((source, path));
+                }
+            }
+        }
+    }
+    found
+}
+
+// Every manifest file must be at its listed path with the listed hash
+fn verify_candidate(
+    candidate: &Path,
+    manifest: &[(String, String)],
+    task: &tasks::TaskHandle,
+) -> Result<(), String> {
+    for (file, hash) in manifest {
+        task.check_cancelled()?;
+        let path = candidate.join(file);
+        if !path.is_file() {
+            return Err(format!("{} is missing", file));
+        }
+        if dedup::file_md5(&path).as_ref() != Some(hash) {
+            return Err(format!("{} has been modified", file));
+        }
+    }
+    Ok(())
+}
+
+fn import_tree(
+    source: &Path,
+    dest: &Path,
+    hardlink: bool,
+    task: &tasks::TaskHandle,
+) -> Result<(), String> {
+    let files = dedup::walk_files(source);
+    let mut percent = 0;
+    for (idx, relative) in files.iter().enumerate() {
+        task.check_cancelled()?;
+        // Fast flags written by other bootstrappers are not ours to carry over
+        if relative.starts_with("ClientSettings") {
+            continue;
+        }
+        let done = 50 + (idx * 50 / files.len()) as u64;
+        if done != percent {
+            percent = done;
+            task.progress("Importing files...", percent);
+        }
+        let from = source.join(relative);
+        let to = dest.join(relative);
+        if let Some(parent) = to.parent() {
+            fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+        }
+        if hardlink && fs::hard_link(&from, &to).is_ok() {
+            continue;
+        }
+        fs::copy(&from, &to).map_err(|e| format!("{}: {}", relative.display(), e))?;
+    }
+    Ok(())
+}
+
+// Imports a verified install of `version` from another launcher into `version_path`.
+pub fn import_matching_install(
+    version: &str,
+    binary_type: &str,
+    version_path: &Path,
+    hardlink: bool,
+    task: &tasks::TaskHandle,
+) -> Result<PathBuf, String> {
+    let candidates: Vec<_> = discover(binary_type)
+        .into_iter()
+        .filter(|(_, path)| path.file_name().map(|n| n == version).unwrap_or(false))
+        .collect();
+    if candidates.is_empty() {
+        return Err(format!("No existing installation of {} found", version));
+    }
+
+    let manifest_text = crate::fetch_manifest_text(version, binary_type, "rbxManifest.txt")?;
+    let manifest = dedup::parse_rbx_manifest(&manifest_text);
+    if manifest.is_empty() {
+        return Err(format!("Manifest for {} is empty", version));
+    }
+    let mut last_error = String::new();
+
+    for (source, candidate) in candidates {
+        task.check_cancelled()?;
+        task.progress(format!("Verifying {} installation...", source), 0);
+        if let Err(e) = verify_candidate(&candidate, &manifest, task) {
+            task.check_cancelled()?;
+            println!("[Adopt] Skipping {}: {}", candidate.display(), e);
+            last_error = format!("{} installation rejected: {}", source, e);
+            continue;
+        }
+
+        task.progress(format!("Importing from {}...", source), 50);
+        if version_path.exists() {
+            let _ = fs::remove_dir_all(version_path);
+        }
+        if let Err(e) = import_tree(&candidate, version_path, hardlink, task) {
+            let _ = fs::remove_dir_all(version_path);
+            task.check_cancelled()?;
+            return Err(format!("Failed to import from {}: {}", source, e));
+        }
+        let _ = fs::write(version_path.join("rbxManifest.txt"), &manifest_text);
+        if let Ok(pkg_manifest) =
+            crate::fetch_manifest_text(version, binary_type, "rbxPkgManifest.txt")
+        {
+            let _ = fs::write(version_path.join("rbxPkgManifest.txt"), pkg_manifest);
+        }
+        println!("[Adopt] Imported {} from {}", version, candidate.display());
+        return Ok(candidate);
+    }
+
+    Err(last_error)
+}
+
+#[tauri::command]
+pub async fn find_existing_installs(binary_type: String) -> Result<Vec<ExistingInstall>, String> {
+    tauri::async_runtime::spawn_blocking(move || {
+        let latest = crate::get_latest_version(&binary_type).ok();
+        Ok(discover(&binary_type)
+            .into_iter()
+            .map(|(source, path)| {
+                let version = path
+                    .file_name()
+                    .map(|n| n.to_string_lossy().to_string())
+                    .unwrap_or_default();
+                ExistingInstall {
+                    source: source.to_string(),
+                    path: path.to_string_lossy().to_string(),
+                    is_latest: latest.as_deref() == Some(version.as_str()),
+                    version,
+                }
+            })
+            .collect())
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
+
+#[tauri::command]
+pub async fn adopt_existing_install(
+    app: tauri::AppHandle,
+    binary_type: String,
+    hardlink: bool,
+    on_progress: Channel<tasks::TaskEvent>,
+) -> Result<String, String> {
+    let task = tasks::start(
+        &app,
+        "Importing Roblox",
+        tasks::install_key(&binary_type),
+        on_progress,
+    )?;
+    tauri::async_runtime::spawn_blocking(move || {
+        let res = (|| {
+            let version = crate::get_latest_version(&binary_type)?;
+            let version_path = storage::versions_dir(&app)?.join(&version);
+
+            if version_path
+                .join(crate::exe_name_for(&binary_type))
+                .exists()
+            {
+                return Ok(format!("{} is already installed.", version));
+            }
+
+            let source =
+                import_matching_install(&version, &binary_type, &version_path, hardlink, &task)?;
+            Ok(format!("Imported {} from {}", version, source.display()))
+        })();
+        task.finish(&res);
+        res
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
@@ -0,0 +1,221 @@
+use md5::{Digest, Md5};
+use std::collections::HashMap;
+use std::fs;
+use std::io::Read;
+use std::path::{Path, PathBuf};
+
+// rbxManifest.txt alternates a relative file path (backslash separated) and its MD5
+pub fn parse_rbx_manifest(content: &str) -> Vec<(String, String)> {
+    let mut entries = Vec::new();
+    let lines: Vec<&str> = content.lines().map(|l| l.trim()).collect();
+    for pair in lines.chunks(2) {
+        if let [file, hash] = pair {
+            if !file.is_empty() && !hash.is_empty() {
+                entries.push((file.replace('\\', "/"), hash.to_lowercase()));
+            }
+        }
+    }
+    entries
+}
+
+pub fn read_rbx_manifest(path: &Path) -> Vec<(String, String)> {
+    fs::read_to_string(path)
+        .map(|content| parse_rbx_manifest(&content))
+        .unwrap_or_default()
+}
+
+pub fn manifest_key(path: &Path) -> String {
+    path.to_string_lossy().replace('\\', "/").to_lowercase()
+}
+
+pub fn file_md5(path: &Path) -> Option<String> {
+    let mut file = fs::File::open(path).ok()?;
+    let mut hasher = Md5::new();
+    let mut buf = [0u8; 64 * 1024];
+    loop {
+        let n = file.read(&mut buf).ok()?;
+        if n == 0 {
+            break;
+        }
+        hasher.update(&buf[..n]);
+    }
+    Some(
+        hasher
+            .finalize()
+            .iter()
+            .map(|b| format!("{:02x}", b))
+            .collect(),
+    )
+}
+
+pub fn bytes_md5(bytes: &[u8]) -> String {
+    Md5::digest(bytes)
+        .iter()
+        .map(|b| format!("{:02x}", b))
+        .collect()
+}
+
+// Files from other installed versions whose manifest MD5 matches the version being installed
+pub struct DonorIndex {
+    expected: HashMap<String, String>,
+    donors: HashMap<String, PathBuf>,
+}
+
+impl DonorIndex {
+    pub fn build(app: &tauri::AppHandle, version_path: &Path) -> DonorIndex {
+        let expected: HashMap<String, String> =
+            read_rbx_manifest(&version_path.join("rbxManifest.txt"))
+                .into_iter()
+                .map(|(file, hash)| (manifest_key(Path::new(&file)), hash))
+                .collect();
+        let mut donors = HashMap::new();
+
+        let versions_dir = match crate::storage::versions_dir(app) {
+            Ok(dir) => dir,
+            Err(_) => return DonorIndex { expected, donors },
+        };
+
+        if !expected.is_empty() {
+            if let Ok(entries) = fs::read_dir(&versions_dir) {
+                for entry in entries.flatten() {
+                    let donor_path = entry.path();
+                    if !donor_path.is_dir() || donor_path == version_path {
+                        continue;
+                    }
+                    let donor_manifest = read_rbx_manifest(&donor_path.join("rbxManifest.txt"));
+                    for (file, hash) in donor_manifest {
+                        let key = manifest_key(Path::new(&file));
+                        if donors.contains_key(&key) || expected.get(&key) != Some(&hash) {
+                            continue;
+                        }
+                        donors.insert(key, donor_path.join(&file));
+                    }
+                }
+            }
+        }
+
+        println!(
+            "[Dedup] {} of {} files can be shared with installed versions",
+            donors.len(),
+            expected.len()
+        );
+        DonorIndex { expected, donors }
+    }
+
+    // Shares an identical file from another version with `outpath`, as a
+    // reflink where the filesystem supports one and a hardlink otherwise.
+    // The donor is re-hashed first since mods may have rewritten it in place.
+    pub fn link(&self, relative: &Path, outpath: &Path, size: u64) -> bool {
+        let key = manifest_key(relative);
+        let (donor, hash) = match (self.donors.get(&key), self.expected.get(&key)) {
+            (Some(donor), Some(hash)) => (donor, hash),
+            _ => return false,
+        };
+
+        match fs::metadata(donor) {
+            Ok(meta) if meta.is_file() && meta.len() == size => {}
+            _ => return false,
+        }
+        if file_md5(donor).as_ref() != Some(hash) {
+            return false;
+        }
+
+        let _ = fs::remove_file(outpath);
+        if reflink(donor, outpath) {
+            return true;
+        }
+        fs::hard_link(donor, outpath).is_ok()
+    }
+}
+
+// A reflink shares the data blocks but, unlike a hardlink, gives `dest` its
+// own copy as soon as either file is written. Btrfs and XFS support it.
+#[cfg(target_os = "linux")]
+fn reflink(source: &Path, dest: &Path) -> bool {
+    use std::os::fd::AsRawFd;
+
+    let Ok(src) = fs::File::open(source) else {
+        return false;
+    };
+    let Ok(dst) = fs::File::create_new(dest) else {
+        return false;
+    };
+    // SAFETY: both descriptors are open for the duration of the call
+    let cloned = unsafe { libc::ioctl(dst.as_raw_fd(), libc::FICLONE, src.as_raw_fd()) } == 0;
+    if !cloned {
+        drop(dst);
+        let _ = fs::remove_file(dest);
+    }
+    cloned
+}
+
+#[cfg(not(target_os = "linux"))]
+fn reflink(_source: &Path, _dest: &Path) -> bool {
+    false
+}
+
+// Writing through a hardlink would change every version sharing the file,
+// so mods must replace the link with a fresh file instead.
+pub fn unlink_before_write(path: &Path) {
+    if path.is_file() {
+        let _ = fs::remove_file(path);
+    }
+}
+
+// Relative paths of every regular file below `root`
+pub fn walk_files(root: &Path) -> Vec<PathBuf> {
+    let mut files = Vec::new();
+    let mut pending = vec![PathBuf::new()];
+    while let Some(relative) = pending.pop() {
+        if let Ok(entries) = fs::read_dir(root.join(&relative)) {
+            for entry in entries.flatten() {
+                let child = relative.join(entry.file_name());
+                match entry.file_type() {
+                    Ok(t) if t.is_dir() => pending.push(child),
+                    Ok(t) if t.is_file() => files.push(child),
+                    _ => {}
+                }
+            }
+        }
+    }
+    files
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn manifest_pairs_paths_with_hashes() {
+        let entries = parse_rbx_manifest(
+            "content\\fonts\\arial.ttf\r\nD41D8CD98F00B204E9800998ECF8427E\r\nRobloxPlayerBeta.exe\r\n0cc175b9c0f1b6a831c399e269772661\r\nno-hash.txt\r\n",
+        );
+        assert_eq!(
+            entries,
+            vec![
+                (
+                    "content/fonts/arial.ttf".to_string(),
+                    "d41d8cd98f00b204e9800998ecf8427e".to_string()
+                ),
+                (
+                    "RobloxPlayerBeta.exe".to_string(),
+                    "0cc175b9c0f1b6a831c399e269772661".to_string()
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn missing_manifest_is_empty() {
+        assert!(parse_rbx_manifest("").is_empty());
+        assert!(read_rbx_manifest(Path::new("/nonexistent/rbxManifest.txt")).is_empty());
+    }
+
+    #[test]
+    fn keys_ignore_case_and_separators() {
+        assert_eq!(
+            manifest_key(Path::new("Content\\Fonts\\Arial.TTF")),
+            manifest_key(Path::new("content/fonts/arial.ttf"))
+        );
+    }
+}
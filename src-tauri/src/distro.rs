@@ -0,0 +1,165 @@
+use std::fs;
+use std::process::Command;
+
+use crate::flatpak::command_exists;
+
+#[derive(Clone, Default)]
+pub struct OsRelease {
+    pub id: String,
+    pub id_like: Vec<String>,
+    pub pretty_name: String,
+}
+
+impl OsRelease {
+    // ID first, then whatever it declares itself to be based on
+    pub fn family(&self) -> impl Iterator<Item = &str> {
+        std::iter::once(self.id.as_str()).chain(self.id_like.iter().map(|s| s.as_str()))
+    }
+}
+
+pub fn os_release() -> OsRelease {
+    let content = fs::read_to_string("/etc/os-release")
+        .or_else(|_| fs::read_to_string("/usr/lib/os-release"))
+        .unwrap_or_default();
+    parse_os_release(&content)
+}
+
+fn parse_os_release(content: &str) -> OsRelease {
+    let mut release = OsRelease::default();
+    for line in content.lines() {
+        let Some((key, value)) = line.split_once('=') else {
+            continue;
+        };
+        let value = value.trim().trim_matches('"').trim_matches('\'');
+        match key.trim() {
+            "ID" => release.id = value.to_lowercase(),
+            "ID_LIKE" => {
+                release.id_like = value.split_whitespace().map(|s| s.to_lowercase()).collect()
+            }
+            "PRETTY_NAME" => release.pretty_name = value.to_string(),
+            _ => {}
+        }
+    }
+    if release.pretty_name.is_empty() {
+        release.pretty_name = if release.id.is_empty() {
+            "Unknown Linux".to_string()
+        } else {
+            release.id.clone()
+        };
+    }
+    release
+}
+
+// Root command (run through sh) that installs `package` with the distro's package manager
+pub fn install_command(release: &OsRelease, package: &str) -> Option<String> {
+    for id in release.family() {
+        let command = match id {
+            "debian" | "ubuntu" => {
+                format!("apt-get update && apt-get install -y {}", package)
+            }
+            "fedora" | "rhel" | "centos" => format!("dnf install -y {}", package),
+            "arch" => format!("pacman -S --needed --noconfirm {}", package),
+            "opensuse" | "suse" => format!("zypper --non-interactive install {}", package),
+            "void" => format!("xbps-install -Sy {}", package),
+            "alpine" => format!("apk add {}", package),
+            "solus" => format!("eopkg install -y {}", package),
+            _ => continue,
+        };
+        return Some(command);
+    }
+    None
+}
+
+// The same command as the user would type it into a terminal
+pub fn with_sudo(command: &str) -> String {
+    command
+        .split(" && ")
+        .map(|part| format!("sudo {}", part))
+        .collect::<Vec<_>>()
+        .join(" && ")
+}
+
+pub fn can_escalate() -> bool {
+    command_exists("pkexec")
+}
+
+// Runs a root command through polkit, which asks for the password with a GUI dialog
+pub fn run_privileged(command: &str) -> Result<(), String> {
+    let output = Command::new("pkexec")
+        .args(["sh", "-c", command])
+        .output()
+        .map_err(|e| format!("Failed to run pkexec: {}", e))?;
+
+    match output.status.code() {
+        Some(0) => Ok(()),
+        Some(126) => Err("Authentication was cancelled".to_string()),
+        Some(127) => {
+            Err("Not authorized, or no polkit authentication agent is running".to_string())
+        }
+        _ => {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            let detail = stderr
+                .lines()
+                .map(|l| l.trim())
+                .rfind(|l| !l.is_empty())
+                .unwrap_or("no output");
+            Err(format!("`{}` failed: {}", command, detail))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_quoted_values_and_id_like() {
+        let release = parse_os_release(
+            "NAME=\"Linux Mint\"\nID=linuxmint\nID_LIKE=\"ubuntu debian\"\nPRETTY_NAME=\"Linux Mint 22\"\n",
+        );
+        assert_eq!(release.id, "linuxmint");
+        assert_eq!(release.id_like, vec!["ubuntu", "debian"]);
+        assert_eq!(release.pretty_name, "Linux Mint 22");
+    }
+
+    #[test]
+    fn falls_back_to_the_id_for_a_name() {
+        assert_eq!(parse_os_release("ID='void'\n").pretty_name, "void");
+        assert_eq!(parse_os_release("").pretty_name, "Unknown Linux");
+    }
+
+    #[test]
+    fn derivatives_use_their_base_package_manager() {
+        let mint = parse_os_release("ID=linuxmint\nID_LIKE=\"ubuntu debian\"\n");
+        assert_eq!(
+            install_command(&mint, "flatpak").as_deref(),
+            Some("apt-get update && apt-get install -y flatpak")
+        );
+        let manjaro = parse_os_release("ID=manjaro\nID_LIKE=arch\n");
+        assert_eq!(
+            install_command(&manjaro, "flatpak").as_deref(),
+            Some("pacman -S --needed --noconfirm flatpak")
+        );
+        let tumbleweed = parse_os_release("ID=opensuse-tumbleweed\nID_LIKE=\"opensuse suse\"\n");
+        assert_eq!(
+            install_command(&tumbleweed, "flatpak").as_deref(),
+            Some("zypper --non-interactive install flatpak")
+        );
+    }
+
+    #[test]
+    fn unknown_distros_have_no_command() {
+        assert_eq!(
+            install_command(&parse_os_release("ID=nixos\n"), "flatpak"),
+            None
+        );
+    }
+
+    #[test]
+    fn sudo_goes_before_every_step() {
+        assert_eq!(
+            with_sudo("apt-get update && apt-get install -y flatpak"),
+            "sudo apt-get update && sudo apt-get install -y flatpak"
+        );
+    }
+}
@@ -0,0 +1,305 @@
+use std::io::{BufRead, BufReader, Read};
+use std::process::{Command, Stdio};
+use tauri::Emitter;
+
+use crate::distro;
+use crate::settings;
+use crate::tasks;
+
+pub const SOBER_APP_ID: &str = "org.vinegarhq.Sober";
+pub const VINEGAR_APP_ID: &str = "org.vinegarhq.Vinegar";
+const FLATHUB_URL: &str = "https://flathub.org/repo/flathub.flatpakrepo";
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug, serde::Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Installation {
+    User,
+    System,
+}
+
+impl Installation {
+    pub fn flag(self) -> &'static str {
+        match self {
+            Installation::User => "--user",
+            Installation::System => "--system",
+        }
+    }
+
+    // Where new installs go; a per-user install needs no root
+    pub fn preferred(app: &tauri::AppHandle) -> Self {
+        if settings::load(app).flatpak_installation == "system" {
+            Installation::System
+        } else {
+            Installation::User
+        }
+    }
+}
+
+// Only Sober and Vinegar are managed; other apps are left alone
+pub fn check_app_id(app_id: &str) -> Result<(), String> {
+    if app_id == SOBER_APP_ID || app_id == VINEGAR_APP_ID {
+        Ok(())
+    } else {
+        Err(format!(
+            "Only Sober and Vinegar can be managed by nullstrap, not {}",
+            app_id
+        ))
+    }
+}
+
+pub fn command_exists(cmd: &str) -> bool {
+    Command::new("sh")
+        .arg("-c")
+        .arg(format!("command -v {} >/dev/null 2>&1", cmd))
+        .status()
+        .map(|s| s.success())
+        .unwrap_or(false)
+}
+
+pub fn stderr_summary(stderr: &[u8]) -> String {
+    String::from_utf8_lossy(stderr)
+        .lines()
+        .map(|l| l.trim())
+        .rfind(|l| !l.is_empty())
+        .unwrap_or("no output")
+        .trim_start_matches("error: ")
+        .to_string()
+}
+
+#[derive(Clone, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FlatpakSetup {
+    installed: bool,
+    distro: String,
+    // Shown to the user for copying when it has to be run by hand
+    command: Option<String>,
+    can_escalate: bool,
+    message: String,
+}
+
+pub fn setup_status() -> FlatpakSetup {
+    let release = distro::os_release();
+    let installed = command_exists("flatpak");
+    let command = distro::install_command(&release, "flatpak");
+    let can_escalate = distro::can_escalate();
+
+    let message = if installed {
+        "Flatpak is installed.".to_string()
+    } else if command.is_none() {
+        format!(
+            "Flatpak is not installed and nullstrap does not know how to install it on {}. See https://flatpak.org/setup/",
+            release.pretty_name
+        )
+    } else if can_escalate {
+        "Flatpak is required to run Sober and Vinegar. Install it now?".to_string()
+    } else {
+        "Flatpak is required to run Sober and Vinegar. Run this command in a terminal, then try again.".to_string()
+    };
+
+    FlatpakSetup {
+        installed,
+        distro: release.pretty_name,
+        command: command.as_deref().map(distro::with_sudo),
+        can_escalate,
+        message,
+    }
+}
+
+// Never installs anything itself: a GUI app has no terminal to ask for a
+// password, so the UI is told and the user decides via install_flatpak.
+pub fn ensure_flatpak(app: &tauri::AppHandle) -> Result<(), String> {
+    if command_exists("flatpak") {
+        return Ok(());
+    }
+    let setup = setup_status();
+    let message = setup.message.clone();
+    let _ = app.emit("flatpak-setup-required", setup);
+    Err(message)
+}
+
+#[tauri::command]
+pub fn get_flatpak_setup() -> FlatpakSetup {
+    setup_status()
+}
+
+#[tauri::command]
+pub async fn install_flatpak() -> Result<FlatpakSetup, String> {
+    tauri::async_runtime::spawn_blocking(|| {
+        if command_exists("flatpak") {
+            return Ok(setup_status());
+        }
+        let release = distro::os_release();
+        let command = distro::install_command(&release, "flatpak").ok_or(format!(
+            "Installing Flatpak automatically is not supported on {}",
+            release.pretty_name
+        ))?;
+        if !distro::can_escalate() {
+            return Err(format!(
+                "pkexec is not available. Run `{}` in a terminal instead.",
+                distro::with_sudo(&command)
+            ));
+        }
+
+        println!("[Flatpak] Installing flatpak on {}", release.pretty_name);
+        distro::run_privileged(&command)?;
+        let setup = setup_status();
+        if setup.installed {
+            Ok(setup)
+        } else {
+            Err("The package manager finished but flatpak is still not available".to_string())
+        }
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
+
+// Which installation, if any, already has the app
+pub fn installed_in(app_id: &str) -> Option<Installation> {
+    [Installation::User, Installation::System]
+        .into_iter()
+        .find(|installation| {
+            Command::new("flatpak")
+                .args(["info", installation.flag(), app_id])
+                .stdout(Stdio::null())
+                .stderr(Stdio::null())
+                .status()
+                .map(|s| s.success())
+                .unwrap_or(false)
+        })
+}
+
+fn ensure_remote(installation: Installation) -> Result<(), String> {
+    let output = Command::new("flatpak")
+        .args([
+            "remote-add",
+            installation.flag(),
+            "--if-not-exists",
+            "flathub",
+            FLATHUB_URL,
+        ])
+        .output()
+        .map_err(|e| format!("Failed to run flatpak: {}", e))?;
+    if output.status.success() {
+        Ok(())
+    } else {
+        Err(format!(
+            "Could not add the Flathub remote: {}",
+            stderr_summary(&output.stderr)
+        ))
+    }
+}
+
+fn last_percent(line: &str) -> Option<u64> {
+    line.split_whitespace()
+        .rev()
+        .find_map(|word| word.strip_suffix('%')?.parse().ok())
+}
+
+// Runs a flatpak install/update, forwarding its progress percentages to the
+// task as `status`. Killed when the task is cancelled.
+pub fn run_transaction(
+    args: &[String],
+    status: &str,
+    task: Option<&tasks::TaskHandle>,
+) -> Result<(), String> {
+    let mut child = Command::new("flatpak")
+        .args(args)
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(|e| format!("Failed to run flatpak: {}", e))?;
+
+    let mut stderr = child.stderr.take().ok_or("flatpak stderr unavailable")?;
+    let stderr_reader = std::thread::spawn(move || {
+        let mut buf = Vec::new();
+        let _ = stderr.read_to_end(&mut buf);
+        buf
+    });
+
+    // flatpak rewrites its progress line with \r, so split on both line endings
+    if let Some(stdout) = child.stdout.take() {
+        let mut reader = BufReader::new(stdout);
+        let mut chunk = Vec::new();
+        loop {
+            chunk.clear();
+            match reader.read_until(b'\r', &mut chunk) {
+                Ok(0) | Err(_) => break,
+                Ok(_) => {}
+            }
+            if let Some(task) = task {
+                if task.check_cancelled().is_err() {
+                    let _ = child.kill();
+                    let _ = child.wait();
+                    return Err(tasks::CANCELLED.to_string());
+                }
+                for line in String::from_utf8_lossy(&chunk).split('\n') {
+                    let line = line.trim();
+                    if line.is_empty() {
+                        continue;
+                    }
+                    println!("[Flatpak] {}", line);
+                    if let Some(percent) = last_percent(line) {
+                        task.progress(status, percent.min(100));
+                    }
+                }
+            }
+        }
+    }
+
+    let exit = child
+        .wait()
+        .map_err(|e| format!("flatpak did not finish: {}", e))?;
+    let stderr = stderr_reader.join().unwrap_or_default();
+    if exit.success() {
+        Ok(())
+    } else {
+        Err(stderr_summary(&stderr))
+    }
+}
+
+fn install(
+    app_id: &str,
+    installation: Installation,
+    task: Option<&tasks::TaskHandle>,
+) -> Result<(), String> {
+    let args: Vec<String> = [
+        "install",
+        installation.flag(),
+        "-y",
+        "--noninteractive",
+        "flathub",
+        app_id,
+    ]
+    .iter()
+    .map(|s| s.to_string())
+    .collect();
+    run_transaction(&args, &format!("Installing {}...", app_id), task).map_err(|e| {
+        format!(
+            "Installing {} ({}) failed: {}",
+            app_id,
+            installation.flag().trim_start_matches("--"),
+            e
+        )
+    })
+}
+
+// Makes sure the app is available, installing it from Flathub when it is missing
+pub fn ensure_app(
+    app: &tauri::AppHandle,
+    app_id: &str,
+    task: Option<&tasks::TaskHandle>,
+) -> Result<Installation, String> {
+    ensure_flatpak(app)?;
+    if let Some(installation) = installed_in(app_id) {
+        return Ok(installation);
+    }
+
+    let installation = Installation::preferred(app);
+    println!("[Flatpak] Installing {} ({})", app_id, installation.flag());
+    if let Some(task) = task {
+        task.progress(format!("Installing {}...", app_id), 0);
+    }
+    ensure_remote(installation)?;
+    install(app_id, installation, task)?;
+    Ok(installation)
+}
@@ -0,0 +1,266 @@
+use std::collections::BTreeMap;
+use std::process::Command;
+
+use crate::flatpak::{self, check_app_id};
+
+const DEVICES: &[&str] = &["dri", "input", "kvm", "shm", "usb", "all"];
+const SOCKETS: &[&str] = &[
+    "x11",
+    "wayland",
+    "fallback-x11",
+    "inherit-wayland-socket",
+    "pulseaudio",
+    "system-bus",
+    "session-bus",
+    "ssh-auth",
+    "pcsc",
+    "cups",
+    "gpg-agent",
+];
+
+// Per-user overrides as `flatpak override --user --show` reports them.
+// Entries starting with "!" take a permission away instead of granting it.
+#[derive(Clone, Default, serde::Serialize, serde::Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct FlatpakOverrides {
+    filesystems: Vec<String>,
+    devices: Vec<String>,
+    sockets: Vec<String>,
+    env: BTreeMap<String, String>,
+}
+
+fn run_override(args: &[String]) -> Result<String, String> {
+    if !flatpak::command_exists("flatpak") {
+        return Err("Flatpak is not installed".to_string());
+    }
+    let output = Command::new("flatpak")
+        .arg("override")
+        .arg("--user")
+        .args(args)
+        .output()
+        .map_err(|e| format!("Failed to run flatpak: {}", e))?;
+    if output.status.success() {
+        Ok(String::from_utf8_lossy(&output.stdout).to_string())
+    } else {
+        Err(format!(
+            "flatpak override failed: {}",
+            flatpak::stderr_summary(&output.stderr)
+        ))
+    }
+}
+
+fn parse_list(value: &str) -> Vec<String> {
+    value
+        .split(';')
+        .map(|s| s.trim())
+        .filter(|s| !s.is_empty())
+        .map(|s| s.to_string())
+        .collect()
+}
+
+fn parse_overrides(keyfile: &str) -> FlatpakOverrides {
+    let mut overrides = FlatpakOverrides::default();
+    let mut section = String::new();
+    for line in keyfile.lines() {
+        let line = line.trim();
+        if line.starts_with('[') && line.ends_with(']') {
+            section = line[1..line.len() - 1].to_string();
+            continue;
+        }
+        let Some((key, value)) = line.split_once('=') else {
+            continue;
+        };
+        match (section.as_str(), key) {
+            ("Context", "filesystems") => overrides.filesystems = parse_list(value),
+            ("Context", "devices") => overrides.devices = parse_list(value),
+            ("Context", "sockets") => overrides.sockets = parse_list(value),
+            ("Environment", _) => {
+                overrides.env.insert(key.to_string(), value.to_string());
+            }
+            _ => {}
+        }
+    }
+    overrides
+}
+
+fn read_overrides(app_id: &str) -> Result<FlatpakOverrides, String> {
+    let shown = run_override(&["--show".to_string(), app_id.to_string()])?;
+    Ok(parse_overrides(&shown))
+}
+
+// "x" becomes --<grant>=x and "!x" becomes --<revoke>=x
+fn permission_args(
+    entries: &[String],
+    grant: &str,
+    revoke: &str,
+    allowed: Option<&[&str]>,
+) -> Result<Vec<String>, String> {
+    let mut args = Vec::new();
+    for entry in entries {
+        let (flag, value) = match entry.strip_prefix('!') {
+            Some(value) => (revoke, value.trim()),
+            None => (grant, entry.trim()),
+        };
+        if value.is_empty() || value.contains(';') || value.contains('\n') {
+            return Err(format!("Invalid {} entry: {:?}", grant, entry));
+        }
+        if let Some(allowed) = allowed {
+            if !allowed.contains(&value) {
+                return Err(format!(
+                    "Unknown {} {}. Expected one of: {}",
+                    grant,
+                    value,
+                    allowed.join(", ")
+                ));
+            }
+        }
+        args.push(format!("--{}={}", flag, value));
+    }
+    Ok(args)
+}
+
+fn override_args(overrides: &FlatpakOverrides) -> Result<Vec<String>, String> {
+    let mut args = permission_args(&overrides.filesystems, "filesystem", "nofilesystem", None)?;
+    args.extend(permission_args(
+        &overrides.devices,
+        "device",
+        "nodevice",
+        Some(DEVICES),
+    )?);
+    args.extend(permission_args(
+        &overrides.sockets,
+        "socket",
+        "nosocket",
+        Some(SOCKETS),
+    )?);
+    for (key, value) in &overrides.env {
+        let valid_key = !key.is_empty()
+            && !key.starts_with(|c: char| c.is_ascii_digit())
+            && key.chars().all(|c| c.is_ascii_alphanumeric() || c == '_');
+        if !valid_key || value.contains('\n') {
+            return Err(format!("Invalid environment variable {:?}", key));
+        }
+        args.push(format!("--env={}={}", key, value));
+    }
+    Ok(args)
+}
+
+// Flatpak keeps a revoke as an override of its own, so entries can't be taken
+// out one by one without revoking what the manifest grants. The section is
+// reset and rebuilt from `overrides` in a single call instead.
+fn replace_overrides(app_id: &str, overrides: &FlatpakOverrides) -> Result<(), String> {
+    run_override(&["--reset".to_string(), app_id.to_string()])?;
+    let mut args = override_args(overrides)?;
+    if args.is_empty() {
+        return Ok(());
+    }
+    args.push(app_id.to_string());
+    run_override(&args).map(|_| ())
+}
+
+#[tauri::command]
+pub async fn get_flatpak_overrides(app_id: String) -> Result<FlatpakOverrides, String> {
+    check_app_id(&app_id)?;
+    tauri::async_runtime::spawn_blocking(move || read_overrides(&app_id))
+        .await
+        .map_err(|e| e.to_string())?
+}
+
+// Replaces the app's user overrides with exactly `overrides`
+#[tauri::command]
+pub async fn set_flatpak_overrides(
+    app_id: String,
+    overrides: FlatpakOverrides,
+) -> Result<FlatpakOverrides, String> {
+    check_app_id(&app_id)?;
+    // Validate everything before touching the existing overrides
+    override_args(&overrides)?;
+    tauri::async_runtime::spawn_blocking(move || {
+        let previous = read_overrides(&app_id)?;
+        if let Err(e) = replace_overrides(&app_id, &overrides) {
+            // Put back what was there rather than leave the section reset
+            let _ = replace_overrides(&app_id, &previous);
+            return Err(e);
+        }
+        println!("[Flatpak] Updated overrides for {}", app_id);
+        read_overrides(&app_id)
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
+
+#[tauri::command]
+pub async fn reset_flatpak_overrides(app_id: String) -> Result<(), String> {
+    check_app_id(&app_id)?;
+    tauri::async_runtime::spawn_blocking(move || {
+        run_override(&["--reset".to_string(), app_id.clone()])?;
+        println!("[Flatpak] Reset overrides for {}", app_id);
+        Ok(())
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn strings(values: &[&str]) -> Vec<String> {
+        values.iter().map(|v| v.to_string()).collect()
+    }
+
+    #[test]
+    fn show_output_round_trips_into_args() {
+        let shown = "[Context]\nfilesystems=xdg-download;!home;\nsockets=wayland;!x11;\n\n[Environment]\nDXVK_HUD=fps\n";
+        let overrides = parse_overrides(shown);
+        assert_eq!(overrides.filesystems, strings(&["xdg-download", "!home"]));
+        assert_eq!(overrides.sockets, strings(&["wayland", "!x11"]));
+        assert_eq!(
+            override_args(&overrides).unwrap(),
+            strings(&[
+                "--filesystem=xdg-download",
+                "--nofilesystem=home",
+                "--socket=wayland",
+                "--nosocket=x11",
+                "--env=DXVK_HUD=fps",
+            ])
+        );
+    }
+
+    // Removing an entry must not turn into its opposite, which flatpak would
+    // keep as an override of its own
+    #[test]
+    fn removed_entries_produce_no_flags() {
+        let wanted = FlatpakOverrides {
+            devices: strings(&["dri"]),
+            ..Default::default()
+        };
+        assert_eq!(override_args(&wanted).unwrap(), strings(&["--device=dri"]));
+        assert!(override_args(&FlatpakOverrides::default())
+            .unwrap()
+            .is_empty());
+    }
+
+    #[test]
+    fn rejects_unknown_and_malformed_entries() {
+        let unknown_device = FlatpakOverrides {
+            devices: strings(&["gpu"]),
+            ..Default::default()
+        };
+        assert!(override_args(&unknown_device).is_err());
+
+        let injected = FlatpakOverrides {
+            filesystems: strings(&["home;host"]),
+            ..Default::default()
+        };
+        assert!(override_args(&injected).is_err());
+
+        let mut env = BTreeMap::new();
+        env.insert("1BAD".to_string(), "x".to_string());
+        assert!(override_args(&FlatpakOverrides {
+            env,
+            ..Default::default()
+        })
+        .is_err());
+    }
+}
@@ -0,0 +1,354 @@
+use std::process::Command;
+use tauri::ipc::Channel;
+
+use crate::flatpak::{self, check_app_id};
+use crate::linux_apps::{self, ResolvedApp};
+use crate::tasks;
+
+#[derive(Clone, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FlatpakAppVersion {
+    app_id: String,
+    kind: String,
+    version: Option<String>,
+    commit: String,
+    branch: String,
+    origin: String,
+    // Masked, so `flatpak update` leaves it on its current commit
+    pinned: bool,
+    update_available: bool,
+}
+
+#[derive(Clone, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FlatpakCommit {
+    commit: String,
+    subject: String,
+    date: String,
+}
+
+// `flatpak info` and friends translate their labels, so always ask in English
+fn flatpak_output(args: &[String]) -> Result<String, String> {
+    let output = Command::new("flatpak")
+        .args(args)
+        .env("LC_ALL", "C")
+        .output()
+        .map_err(|e| format!("Failed to run flatpak: {}", e))?;
+    if output.status.success() {
+        Ok(String::from_utf8_lossy(&output.stdout).to_string())
+    } else {
+        Err(flatpak::stderr_summary(&output.stderr))
+    }
+}
+
+fn args(parts: &[&str], installation: &[String], app_id: &str) -> Vec<String> {
+    let mut args: Vec<String> = parts.iter().map(|s| s.to_string()).collect();
+    args.extend(installation.iter().cloned());
+    args.push(app_id.to_string());
+    args
+}
+
+// "  Key: value" lines as printed by `flatpak info` and `flatpak remote-info`
+fn fields(output: &str) -> impl Iterator<Item = (&str, &str)> {
+    output.lines().filter_map(|line| {
+        let (key, value) = line.split_once(':')?;
+        Some((key.trim(), value.trim()))
+    })
+}
+
+// `remote-info --log` prints the current commit's fields, then a "History:"
+// section with one Commit/Subject/Date group per earlier commit
+fn parse_log(log: &str) -> Vec<FlatpakCommit> {
+    let mut commits: Vec<FlatpakCommit> = Vec::new();
+    for (key, value) in fields(log) {
+        match (key, commits.last_mut()) {
+            ("Commit", _) => commits.push(FlatpakCommit {
+                commit: value.to_string(),
+                subject: String::new(),
+                date: String::new(),
+            }),
+            ("Subject", Some(last)) => last.subject = value.to_string(),
+            ("Date", Some(last)) => last.date = value.to_string(),
+            _ => {}
+        }
+    }
+    commits
+}
+
+fn installed_app(app: &tauri::AppHandle, app_id: &str) -> Result<ResolvedApp, String> {
+    check_app_id(app_id)?;
+    let resolved =
+        linux_apps::resolve(app, app_id).ok_or(format!("{} is not installed", app_id))?;
+    if !resolved.is_flatpak() {
+        return Err(format!(
+            "{} is installed as a {} package. Update it through your package manager.",
+            app_id, resolved.kind
+        ));
+    }
+    Ok(resolved)
+}
+
+fn is_pinned(resolved: &ResolvedApp) -> bool {
+    let mut list_args = vec!["mask".to_string()];
+    list_args.extend(resolved.flatpak_installation_args());
+    flatpak_output(&list_args)
+        .map(|out| out.lines().any(|line| line.trim() == resolved.app_id))
+        .unwrap_or(false)
+}
+
+fn read_version(resolved: &ResolvedApp) -> Result<FlatpakAppVersion, String> {
+    let installation = resolved.flatpak_installation_args();
+    let info = flatpak_output(&args(&["info"], &installation, &resolved.app_id))?;
+
+    let mut version = FlatpakAppVersion {
+        app_id: resolved.app_id.clone(),
+        kind: resolved.kind.clone(),
+        version: None,
+        commit: String::new(),
+        branch: String::new(),
+        origin: String::new(),
+        pinned: is_pinned(resolved),
+        update_available: false,
+    };
+    for (key, value) in fields(&info) {
+        match key {
+            "Version" => version.version = Some(value.to_string()),
+            "Commit" => version.commit = value.to_string(),
+            "Branch" => version.branch = value.to_string(),
+            "Origin" => version.origin = value.to_string(),
+            _ => {}
+        }
+    }
+
+    // Offline or a missing remote just means no update is known of
+    let updates = flatpak_output(&args(
+        &["remote-ls", "--updates", "--columns=application"],
+        &installation,
+        &version.origin,
+    ))
+    .unwrap_or_default();
+    version.update_available = updates.lines().any(|line| line.trim() == resolved.app_id);
+    Ok(version)
+}
+
+fn check_commit(commit: &str) -> Result<(), String> {
+    if commit.len() == 64 && commit.chars().all(|c| c.is_ascii_hexdigit()) {
+        Ok(())
+    } else {
+        Err(format!("{} is not a Flatpak commit hash", commit))
+    }
+}
+
+fn set_mask(resolved: &ResolvedApp, pinned: bool) -> Result<(), String> {
+    let parts: &[&str] = if pinned {
+        &["mask"]
+    } else {
+        &["mask", "--remove"]
+    };
+    flatpak_output(&args(
+        parts,
+        &resolved.flatpak_installation_args(),
+        &resolved.app_id,
+    ))
+    .map(|_| ())
+}
+
+// Moves the app to `commit` (or updates it when None) while it is unmasked
+fn deploy(
+    resolved: &ResolvedApp,
+    commit: Option<&str>,
+    task: &tasks::TaskHandle,
+) -> Result<(), String> {
+    let mut parts = vec!["update", "-y", "--noninteractive"];
+    let commit_arg;
+    if let Some(commit) = commit {
+        commit_arg = format!("--commit={}", commit);
+        parts.push(&commit_arg);
+    }
+    let status = format!("Updating {}...", resolved.app_id);
+    task.progress(status.as_str(), 0);
+    flatpak::run_transaction(
+        &args(
+            &parts,
+            &resolved.flatpak_installation_args(),
+            &resolved.app_id,
+        ),
+        &status,
+        Some(task),
+    )
+    .map_err(|e| format!("Updating {} failed: {}", resolved.app_id, e))
+}
+
+#[tauri::command]
+pub async fn get_flatpak_app_version(
+    app: tauri::AppHandle,
+    app_id: String,
+) -> Result<FlatpakAppVersion, String> {
+    tauri::async_runtime::spawn_blocking(move || read_version(&installed_app(&app, &app_id)?))
+        .await
+        .map_err(|e| e.to_string())?
+}
+
+// Newest first, as published on the app's remote
+#[tauri::command]
+pub async fn list_flatpak_commits(
+    app: tauri::AppHandle,
+    app_id: String,
+) -> Result<Vec<FlatpakCommit>, String> {
+    tauri::async_runtime::spawn_blocking(move || {
+        let resolved = installed_app(&app, &app_id)?;
+        let installation = resolved.flatpak_installation_args();
+        let origin = fields(&flatpak_output(&args(&["info"], &installation, &app_id))?)
+            .find(|(key, _)| *key == "Origin")
+            .map(|(_, value)| value.to_string())
+            .unwrap_or_else(|| "flathub".to_string());
+
+        let mut log_args = args(&["remote-info", "--log"], &installation, &origin);
+        log_args.push(app_id.clone());
+        Ok(parse_log(&flatpak_output(&log_args)?))
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
+
+#[tauri::command]
+pub async fn update_flatpak_app(
+    app: tauri::AppHandle,
+    app_id: String,
+    on_progress: Channel<tasks::TaskEvent>,
+) -> Result<FlatpakAppVersion, String> {
+    check_app_id(&app_id)?;
+    let task = tasks::start(
+        &app,
+        &format!("Updating {}", app_id),
+        &format!("flatpak:{}", app_id),
+        on_progress,
+    )?;
+    tauri::async_runtime::spawn_blocking(move || {
+        let res = (|| {
+            let resolved = installed_app(&app, &app_id)?;
+            if is_pinned(&resolved) {
+                return Err(format!(
+                    "{} is pinned to its current commit. Unpin it to update.",
+                    app_id
+                ));
+            }
+            deploy(&resolved, None, &task)?;
+            println!("[Flatpak] Updated {}", app_id);
+            read_version(&resolved)
+        })();
+        task.finish(&res);
+        res
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
+
+// Deploys `commit` (an older one to roll back, or None to keep the current
+// one) and masks the app so updates leave it there until unpinned.
+#[tauri::command]
+pub async fn pin_flatpak_app(
+    app: tauri::AppHandle,
+    app_id: String,
+    commit: Option<String>,
+    on_progress: Channel<tasks::TaskEvent>,
+) -> Result<FlatpakAppVersion, String> {
+    if let Some(commit) = &commit {
+        check_commit(commit)?;
+    }
+    check_app_id(&app_id)?;
+    let task = tasks::start(
+        &app,
+        &format!("Pinning {}", app_id),
+        &format!("flatpak:{}", app_id),
+        on_progress,
+    )?;
+    tauri::async_runtime::spawn_blocking(move || {
+        let res = (|| {
+            let resolved = installed_app(&app, &app_id)?;
+            if let Some(commit) = &commit {
+                if is_pinned(&resolved) {
+                    set_mask(&resolved, false)?;
+                }
+                deploy(&resolved, Some(commit), &task)?;
+            }
+            set_mask(&resolved, true)?;
+            let version = read_version(&resolved)?;
+            println!("[Flatpak] Pinned {} to {}", app_id, version.commit);
+            Ok(version)
+        })();
+        task.finish(&res);
+        res
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
+
+#[tauri::command]
+pub async fn unpin_flatpak_app(
+    app: tauri::AppHandle,
+    app_id: String,
+) -> Result<FlatpakAppVersion, String> {
+    tauri::async_runtime::spawn_blocking(move || {
+        let resolved = installed_app(&app, &app_id)?;
+        if is_pinned(&resolved) {
+            set_mask(&resolved, false)?;
+        }
+        println!("[Flatpak] Unpinned {}", app_id);
+        read_version(&resolved)
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const LOG: &str = "
+Sober - Roblox on Linux
+
+          ID: org.vinegarhq.Sober
+         Ref: app/org.vinegarhq.Sober/x86_64/stable
+      Branch: stable
+     Version: 1.4.2
+     Collection: org.flathub.Stable
+    Download: 52.1 MB
+   Installed: 141.3 MB
+     Runtime: org.freedesktop.Platform/x86_64/24.08
+
+      Commit: 1111111111111111111111111111111111111111111111111111111111111111
+      Parent: 2222222222222222222222222222222222222222222222222222222222222222
+     Subject: Update to 1.4.2
+        Date: 2026-10-01 12:00:00 +0000
+
+    History:
+
+      Commit: 2222222222222222222222222222222222222222222222222222222222222222
+     Subject: Update to 1.4.1 (abc1234)
+        Date: 2026-09-20 08:30:00 +0000
+";
+
+    #[test]
+    fn log_lists_commits_newest_first() {
+        let commits = parse_log(LOG);
+        assert_eq!(commits.len(), 2);
+        assert_eq!(commits[0].commit, "1".repeat(64));
+        assert_eq!(commits[0].subject, "Update to 1.4.2");
+        assert_eq!(commits[0].date, "2026-10-01 12:00:00 +0000");
+        assert_eq!(commits[1].commit, "2".repeat(64));
+        assert_eq!(commits[1].subject, "Update to 1.4.1 (abc1234)");
+    }
+
+    #[test]
+    fn fields_before_the_first_commit_are_ignored() {
+        assert!(parse_log("     Subject: orphan\n        Date: never\n").is_empty());
+    }
+
+    #[test]
+    fn commit_hashes_are_checked() {
+        assert!(check_commit(&"a".repeat(64)).is_ok());
+        assert!(check_commit("abc123").is_err());
+        assert!(check_commit(&format!("{};", "a".repeat(63))).is_err());
+    }
+}
@@ -0,0 +1,131 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+use tauri::Manager;
+
+use crate::dedup;
+use crate::mods::Layer;
+
+const FONT_STEM: &str = "CustomFont";
+
+fn font_dir(app: &tauri::AppHandle) -> Result<PathBuf, String> {
+    let data_dir = app.path().app_local_data_dir().map_err(|e| e.to_string())?;
+    Ok(data_dir.join("custom-font"))
+}
+
+// The chosen font, kept as CustomFont.ttf or CustomFont.otf
+fn installed_font(app: &tauri::AppHandle) -> Option<PathBuf> {
+    let dir = font_dir(app).ok()?;
+    ["ttf", "otf"]
+        .iter()
+        .map(|ext| dir.join(format!("{}.{}", FONT_STEM, ext)))
+        .find(|path| path.is_file())
+}
+
+// sfnt version tag: TrueType outlines, or "OTTO" for CFF-based OpenType
+fn font_extension(data: &[u8]) -> Option<&'static str> {
+    match data.get(0..4)? {
+        [0, 1, 0, 0] | b"true" => Some("ttf"),
+        b"OTTO" => Some("otf"),
+        _ => None,
+    }
+}
+
+// Points every face of a family descriptor at the custom font. Returns None
+// when the file is not a family descriptor.
+fn rewrite_family(content: &str, asset_id: &str) -> Option<String> {
+    let mut family: serde_json::Value = serde_json::from_str(content).ok()?;
+    let faces = family.get_mut("faces")?.as_array_mut()?;
+    for face in faces.iter_mut() {
+        face.as_object_mut()?
+            .insert("assetId".to_string(), asset_id.into());
+    }
+    serde_json::to_string_pretty(&family).ok()
+}
+
+// The font file plus a rewritten copy of each family descriptor found in
+// `root`. Rewriting is idempotent, so reading descriptors we replaced on an
+// earlier launch gives the same result.
+pub fn layer(
+    app: &tauri::AppHandle,
+    target_name: &str,
+    root: &Path,
+) -> Result<Option<Layer>, String> {
+    // Sober's font descriptors are inside its Flatpak, so there is nothing to rewrite
+    if target_name == "sober" {
+        return Ok(None);
+    }
+    let Some(font) = installed_font(app) else {
+        return Ok(None);
+    };
+    let font_name = font
+        .file_name()
+        .ok_or("Invalid font file")?
+        .to_string_lossy()
+        .to_string();
+    let asset_id = format!("rbxasset://fonts/{}", font_name);
+
+    let families = Path::new("content").join("fonts").join("families");
+    let generated = font_dir(app)?.join("families");
+    let _ = fs::remove_dir_all(&generated);
+    fs::create_dir_all(&generated).map_err(|e| e.to_string())?;
+
+    let mut files = vec![(Path::new("content").join("fonts").join(&font_name), font)];
+    for relative in dedup::walk_files(&root.join(&families)) {
+        let is_json = relative
+            .extension()
+            .is_some_and(|ext| ext.eq_ignore_ascii_case("json"));
+        if !is_json || relative.components().count() != 1 {
+            continue;
+        }
+        let Ok(content) = fs::read_to_string(root.join(&families).join(&relative)) else {
+            continue;
+        };
+        if let Some(rewritten) = rewrite_family(&content, &asset_id) {
+            let output = generated.join(&relative);
+            fs::write(&output, rewritten).map_err(|e| e.to_string())?;
+            files.push((families.join(&relative), output));
+        }
+    }
+    if files.len() == 1 {
+        println!("[Mods] No font families found in {}", root.display());
+    }
+    Ok(Some(Layer { files }))
+}
+
+#[tauri::command]
+pub fn get_custom_font(app: tauri::AppHandle) -> Option<String> {
+    installed_font(&app).map(|path| path.to_string_lossy().to_string())
+}
+
+// Takes effect the next time mods are applied
+#[tauri::command]
+pub fn set_custom_font(app: tauri::AppHandle, path: String) -> Result<String, String> {
+    let data = fs::read(&path).map_err(|e| format!("{}: {}", path, e))?;
+    let ext = font_extension(&data).ok_or("Only TrueType and OpenType fonts are supported")?;
+    let dir = font_dir(&app)?;
+    fs::create_dir_all(&dir).map_err(|e| e.to_string())?;
+    // The previous font stays until the new one is fully written
+    let previous = installed_font(&app);
+    let target = dir.join(format!("{}.{}", FONT_STEM, ext));
+    let tmp = dir.join(format!("{}.tmp", FONT_STEM));
+    fs::write(&tmp, data).map_err(|e| e.to_string())?;
+    fs::rename(&tmp, &target).map_err(|e| {
+        let _ = fs::remove_file(&tmp);
+        e.to_string()
+    })?;
+    if let Some(previous) = previous.filter(|p| *p != target) {
+        let _ = fs::remove_file(previous);
+    }
+    println!("[Mods] Custom font set to {}", path);
+    Ok(target.to_string_lossy().to_string())
+}
+
+// Roblox's fonts and descriptors come back the next time mods are applied
+#[tauri::command]
+pub fn reset_custom_font(app: tauri::AppHandle) -> Result<(), String> {
+    let dir = font_dir(&app)?;
+    if dir.exists() {
+        fs::remove_dir_all(&dir).map_err(|e| e.to_string())?;
+    }
+    Ok(())
+}
@@ -0,0 +1,351 @@
+use std::collections::HashSet;
+use std::fs;
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tauri::Manager;
+
+use crate::dedup;
+use crate::packages;
+use crate::settings;
+use crate::storage;
+
+const SERVICE_TYPE: &str = "_nullstrap._tcp.local.";
+const DISCOVERY_TIMEOUT: Duration = Duration::from_millis(1500);
+const PEER_TIMEOUT: Duration = Duration::from_secs(30);
+// Discovery blocks for DISCOVERY_TIMEOUT, so its result is reused for a while
+const DISCOVERY_MAX_AGE: Duration = Duration::from_secs(60);
+
+struct RunningServer {
+    server: Arc<tiny_http::Server>,
+    mdns: Option<mdns_sd::ServiceDaemon>,
+    port: u16,
+}
+
+#[derive(Default)]
+pub struct LanState {
+    server: Mutex<Option<RunningServer>>,
+    // Peers found by the last discovery, and when it ran
+    discovered: Mutex<Option<(Instant, Vec<String>)>>,
+}
+
+#[derive(Clone, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LanStatus {
+    serving: bool,
+    port: u16,
+    cached_packages: usize,
+    peers: Vec<String>,
+}
+
+// Verified package zips kept for peers, named by their MD5. Changing the
+// install location moves it along with the versions.
+pub fn cache_dir(app: &tauri::AppHandle) -> Result<PathBuf, String> {
+    Ok(storage::install_root(app)?.join("rblx-packages"))
+}
+
+fn is_md5(name: &str) -> bool {
+    name.len() == 32 && name.chars().all(|c| c.is_ascii_hexdigit())
+}
+
+fn host_name() -> String {
+    sysinfo::System::host_name().unwrap_or_else(|| "nullstrap".to_string())
+}
+
+// Other instances announcing themselves over mDNS, as "ip:port"
+fn discover_peers() -> Vec<String> {
+    let Ok(daemon) = mdns_sd::ServiceDaemon::new() else {
+        return Vec::new();
+    };
+    let Ok(receiver) = daemon.browse(SERVICE_TYPE) else {
+        let _ = daemon.shutdown();
+        return Vec::new();
+    };
+
+    let own_name = format!("{}.{}", host_name(), SERVICE_TYPE);
+    let deadline = Instant::now() + DISCOVERY_TIMEOUT;
+    let mut peers = Vec::new();
+    while let Some(remaining) = deadline.checked_duration_since(Instant::now()) {
+        match receiver.recv_timeout(remaining) {
+            Ok(mdns_sd::ServiceEvent::ServiceResolved(info)) => {
+                if info.get_fullname() == own_name {
+                    continue;
+                }
+                for addr in info.get_addresses_v4() {
+                    let peer = format!("{}:{}", addr, info.get_port());
+                    if !peers.contains(&peer) {
+                        peers.push(peer);
+                    }
+                }
+            }
+            Ok(_) => {}
+            Err(_) => break,
+        }
+    }
+    let _ = daemon.stop_browse(SERVICE_TYPE);
+    let _ = daemon.shutdown();
+    peers
+}
+
+fn cached_peers(app: &tauri::AppHandle) -> Vec<String> {
+    let state = app.state::<LanState>();
+    let Ok(mut discovered) = state.discovered.lock() else {
+        return discover_peers();
+    };
+    match discovered.as_ref() {
+        Some((at, peers)) if at.elapsed() < DISCOVERY_MAX_AGE => peers.clone(),
+        _ => {
+            let peers = discover_peers();
+            *discovered = Some((Instant::now(), peers.clone()));
+            peers
+        }
+    }
+}
+
+// Where package downloads look before the CDN, and where verified ones are kept
+pub struct PeerSource {
+    peers: Vec<String>,
+    cache_dir: Option<PathBuf>,
+}
+
+impl PeerSource {
+    pub fn new(app: &tauri::AppHandle) -> Self {
+        let current = settings::load(app);
+        let mut peers = Vec::new();
+        if current.lan_fetch {
+            peers.extend(current.lan_peers.iter().filter(|p| !p.is_empty()).cloned());
+            for peer in cached_peers(app) {
+                if !peers.contains(&peer) {
+                    peers.push(peer);
+                }
+            }
+            if !peers.is_empty() {
+                println!("[LAN] Peers: {}", peers.join(", "));
+            }
+        }
+        PeerSource {
+            peers,
+            cache_dir: if current.lan_serve {
+                cache_dir(app).ok()
+            } else {
+                None
+            },
+        }
+    }
+
+    // Peers are only trusted for bytes that match the official manifest
+    pub fn fetch(&self, client: &reqwest::blocking::Client, md5: &str) -> Option<Vec<u8>> {
+        for peer in &self.peers {
+            let url = format!("http://{}/packages/{}", peer, md5);
+            let bytes = match client.get(&url).timeout(PEER_TIMEOUT).send() {
+                Ok(resp) if resp.status().is_success() => match resp.bytes() {
+                    Ok(b) => b,
+                    Err(_) => continue,
+                },
+                _ => continue,
+            };
+            if dedup::bytes_md5(&bytes) == md5 {
+                println!("[LAN] Got {} from {}", md5, peer);
+                return Some(bytes.to_vec());
+            }
+            println!("[LAN] {} sent a corrupted copy of {}", peer, md5);
+        }
+        None
+    }
+
+    pub fn store(&self, md5: &str, bytes: &[u8]) {
+        let Some(dir) = &self.cache_dir else {
+            return;
+        };
+        if !is_md5(md5) || fs::create_dir_all(dir).is_err() {
+            return;
+        }
+        // Write then rename so a peer never gets a partial file
+        let tmp = dir.join(format!("{}.part", md5));
+        if fs::write(&tmp, bytes).is_ok() {
+            let _ = fs::rename(&tmp, dir.join(md5));
+        }
+    }
+}
+
+// Drops cached packages that no installed or staged version lists any more
+pub fn prune_cache(app: &tauri::AppHandle) {
+    let Ok(dir) = cache_dir(app) else {
+        return;
+    };
+    if !dir.exists() {
+        return;
+    }
+
+    let mut referenced = HashSet::new();
+    for root in [storage::versions_dir(app), storage::staging_dir(app)]
+        .into_iter()
+        .flatten()
+    {
+        if let Ok(entries) = fs::read_dir(root) {
+            for entry in entries.flatten() {
+                if let Ok(content) = fs::read_to_string(entry.path().join("rbxPkgManifest.txt")) {
+                    referenced.extend(
+                        packages::parse_pkg_manifest(&content)
+                            .into_iter()
+                            .map(|p| p.md5),
+                    );
+                }
+            }
+        }
+    }
+
+    if let Ok(entries) = fs::read_dir(&dir) {
+        for entry in entries.flatten() {
+            let name = entry.file_name().to_string_lossy().to_string();
+            if !referenced.contains(&name) {
+                let _ = fs::remove_file(entry.path());
+            }
+        }
+    }
+}
+
+// The cache directory is looked up per request since changing the install
+// location moves it while the server keeps running
+fn serve(server: Arc<tiny_http::Server>, app: tauri::AppHandle) {
+    for request in server.incoming_requests() {
+        let md5 = request
+            .url()
+            .strip_prefix("/packages/")
+            .map(|s| s.to_lowercase())
+            .filter(|s| is_md5(s));
+        let file = match (request.method(), md5) {
+            (tiny_http::Method::Get, Some(md5)) => cache_dir(&app)
+                .ok()
+                .and_then(|dir| fs::File::open(dir.join(md5)).ok()),
+            _ => None,
+        };
+        let _ = match file {
+            Some(file) => request.respond(tiny_http::Response::from_file(file)),
+            None => request.respond(tiny_http::Response::empty(404)),
+        };
+    }
+}
+
+fn advertise(port: u16) -> Option<mdns_sd::ServiceDaemon> {
+    let daemon = mdns_sd::ServiceDaemon::new().ok()?;
+    let host = host_name();
+    let info = mdns_sd::ServiceInfo::new(
+        SERVICE_TYPE,
+        &host,
+        &format!("{}.local.", host),
+        "",
+        port,
+        &[("version", env!("CARGO_PKG_VERSION"))][..],
+    )
+    .ok()?
+    .enable_addr_auto();
+    if let Err(e) = daemon.register(info) {
+        println!("[LAN] mDNS registration failed: {}", e);
+        let _ = daemon.shutdown();
+        return None;
+    }
+    Some(daemon)
+}
+
+pub fn start_server(app: &tauri::AppHandle) -> Result<(), String> {
+    let state = app.state::<LanState>();
+    let mut running = state.server.lock().map_err(|e| e.to_string())?;
+    if running.is_some() {
+        return Ok(());
+    }
+
+    let port = settings::load(app).lan_port;
+    let dir = cache_dir(app)?;
+    fs::create_dir_all(&dir).map_err(|e| e.to_string())?;
+    let server = Arc::new(
+        tiny_http::Server::http(("0.0.0.0", port))
+            .map_err(|e| format!("Cannot listen on port {}: {}", port, e))?,
+    );
+
+    let server_clone = server.clone();
+    let app_clone = app.clone();
+    std::thread::spawn(move || serve(server_clone, app_clone));
+    println!("[LAN] Serving packages on port {}", port);
+
+    *running = Some(RunningServer {
+        server,
+        mdns: advertise(port),
+        port,
+    });
+    Ok(())
+}
+
+pub fn stop_server(app: &tauri::AppHandle) {
+    let state = app.state::<LanState>();
+    let Ok(mut running) = state.server.lock() else {
+        return;
+    };
+    if let Some(current) = running.take() {
+        current.server.unblock();
+        if let Some(daemon) = current.mdns {
+            let _ = daemon.shutdown();
+        }
+        println!("[LAN] Stopped serving packages");
+    }
+}
+
+#[tauri::command]
+pub async fn get_lan_status(app: tauri::AppHandle) -> Result<LanStatus, String> {
+    tauri::async_runtime::spawn_blocking(move || {
+        let port = app
+            .state::<LanState>()
+            .server
+            .lock()
+            .map_err(|e| e.to_string())?
+            .as_ref()
+            .map(|r| r.port);
+        let cached_packages = cache_dir(&app)
+            .ok()
+            .and_then(|dir| fs::read_dir(dir).ok())
+            .map(|entries| {
+                entries
+                    .flatten()
+                    .filter(|e| is_md5(&e.file_name().to_string_lossy()))
+                    .count()
+            })
+            .unwrap_or(0);
+
+        Ok(LanStatus {
+            serving: port.is_some(),
+            port: port.unwrap_or_else(|| settings::load(&app).lan_port),
+            cached_packages,
+            peers: cached_peers(&app),
+        })
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
+
+#[tauri::command]
+pub fn set_lan_sharing(
+    app: tauri::AppHandle,
+    serve: bool,
+    fetch: bool,
+    peers: Vec<String>,
+) -> Result<(), String> {
+    let mut current = settings::load(&app);
+    current.lan_serve = serve;
+    current.lan_fetch = fetch;
+    current.lan_peers = peers
+        .into_iter()
+        .map(|p| p.trim().to_string())
+        .filter(|p| !p.is_empty())
+        .collect();
+    settings::save(&app, &current)?;
+
+    if serve {
+        start_server(&app)
+    } else {
+        stop_server(&app);
+        if let Ok(dir) = cache_dir(&app) {
+            let _ = fs::remove_dir_all(dir);
+        }
+        Ok(())
+    }
+}
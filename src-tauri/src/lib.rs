@@ -1,21 +1,52 @@
+mod adopt;
+mod dedup;
 #[cfg(target_os = "linux")]
-use dirs;
+mod distro;
+#[cfg(target_os = "linux")]
+mod flatpak;
+#[cfg(target_os = "linux")]
+mod flatpak_overrides;
+#[cfg(target_os = "linux")]
+mod flatpak_versions;
+mod font_mod;
+mod lan;
+#[cfg(target_os = "linux")]
+mod linux_apps;
+mod low_spec;
+mod metadata;
+mod mod_packages;
+mod mod_presets;
+mod mods;
+mod packages;
+mod settings;
+mod skybox;
+mod skybox_convert;
+mod skybox_preview;
+#[cfg(target_os = "linux")]
+mod sober_config;
+#[cfg(target_os = "linux")]
+mod sober_overlay;
+mod storage;
+mod tasks;
+mod tex;
+mod updater;
+#[cfg(target_os = "linux")]
+mod vinegar_config;
+#[cfg(target_os = "linux")]
+mod wine;
+
 use reqwest;
+use std::collections::HashMap;
 use std::fs;
-use std::io::Cursor;
-use std::path::PathBuf;
+use std::io::{Cursor, Read};
+use std::path::{Path, PathBuf};
 use std::process::Command;
 use std::time::{Duration, SystemTime};
 use sysinfo::System;
+use tauri::ipc::Channel;
 use tauri::{Emitter, Manager, WindowEvent};
 use zip::ZipArchive;
 
-#[derive(Clone, serde::Serialize)]
-struct ProgressPayload {
-    status: String,
-    percent: u64,
-}
-
 #[tauri::command]
 fn greet(name: &str) -> String {
     format!("Hello, {}! You've been greeted from Rust!", name)
@@ -127,8 +158,8 @@ fn apply_skybox_texture(
                     .join("Versions"),
             );
         }
-        if let Ok(data_dir) = app.path().app_local_data_dir() {
-            potential_paths.push(data_dir.join("rblx-versions"));
+        if let Ok(versions_dir) = storage::versions_dir(&app) {
+            potential_paths.push(versions_dir);
         }
 
         let mut target_dirs = Vec::new();
@@ -168,6 +199,7 @@ fn apply_skybox_texture(
         let mut count = 0;
         for target_dir in target_dirs {
             let dest_path = target_dir.join(&filename);
+            dedup::unlink_before_write(&dest_path);
             if let Ok(_) = fs::write(dest_path, &data) {
                 count += 1;
             }
@@ -175,9 +207,14 @@ fn apply_skybox_texture(
 
         Ok(format!("Applied {} to {} locations", filename, count))
     }
-    #[cfg(not(target_os = "windows"))]
+    #[cfg(target_os = "linux")]
+    {
+        sober_overlay::write_sky_face(&app, &filename, &data)?;
+        Ok(format!("Applied {} to Sober", filename))
+    }
+    #[cfg(not(any(target_os = "windows", target_os = "linux")))]
     {
-        Err("Skybox changer is only implemented for Windows currently.".to_string())
+        Err("Skybox changer is only implemented for Windows and Linux currently.".to_string())
     }
 }
 
@@ -217,8 +254,8 @@ fn save_fast_flags_internal(
             potential_paths.push(PathBuf::from(program_files).join("Roblox").join("Versions"));
         }
 
-        if let Ok(data_dir) = app.path().app_local_data_dir() {
-            potential_paths.push(data_dir.join("rblx-versions"));
+        if let Ok(versions_dir) = storage::versions_dir(&app) {
+            potential_paths.push(versions_dir);
         }
 
         let mut saved_any = false;
@@ -286,8 +323,8 @@ fn save_fast_flags_internal(
         let mut potential_paths = Vec::new();
         potential_paths.push(PathBuf::from("/Applications/Roblox.app"));
 
-        if let Ok(data_dir) = app.path().app_local_data_dir() {
-            potential_paths.push(data_dir.join("rblx-versions"));
+        if let Ok(versions_dir) = storage::versions_dir(&app) {
+            potential_paths.push(versions_dir);
         }
 
         let mut saved_any = false;
@@ -339,94 +376,27 @@ fn save_fast_flags_internal(
 
     #[cfg(target_os = "linux")]
     {
-        use std::io::Write;
-
-        let home = dirs::home_dir().ok_or("Could not find home directory")?;
-
-        if mode == "studio" {
-            let config_path =
-                home.join(".var/app/org.vinegarhq.Vinegar/config/vinegar/config.toml");
-            if !config_path.exists() {
-                return Err("Vinegar config not found".to_string());
-            }
-
-            let content = fs::read_to_string(&config_path).map_err(|e| e.to_string())?;
-            let mut value: toml::Value = toml::from_str(&content).map_err(|e| e.to_string())?;
+        if mode == "studio" && settings::load(app).studio_backend == settings::StudioBackend::Wine
+        {
+            wine::save_flags(app, &flags_json)?;
 
-            let new_flags: std::collections::HashMap<String, serde_json::Value> =
+            Ok("Saved Studio Fast Flags".to_string())
+        } else if mode == "studio" {
+            let new_flags: serde_json::Value =
                 serde_json::from_str(&flags_json).map_err(|e| e.to_string())?;
-
-            let mut overrides_table = toml::map::Map::new();
-            for (k, v) in new_flags {
-                if let Some(b) = v.as_bool() {
-                    overrides_table.insert(k, toml::Value::Boolean(b));
-                } else if let Some(n) = v.as_i64() {
-                    overrides_table.insert(k, toml::Value::Integer(n));
-                } else if let Some(f) = v.as_f64() {
-                    overrides_table.insert(k, toml::Value::Float(f));
-                } else if let Some(s) = v.as_str() {
-                    overrides_table.insert(k, toml::Value::String(s.to_string()));
-                }
-            }
-
-            if let Some(table) = value.as_table_mut() {
-                table.insert("fflags".to_string(), toml::Value::Table(overrides_table));
-            } else {
-                return Err("Invalid vinegar config structure".to_string());
-            }
-
-            let new_toml = toml::to_string(&value).map_err(|e| e.to_string())?;
-            fs::write(&config_path, new_toml).map_err(|e| e.to_string())?;
+            vinegar_config::update(app, &serde_json::json!({ "fflags": new_flags }))?;
 
             Ok("Saved to Vinegar config".to_string())
         } else if mode == "sober_main" {
-            let config_path = home.join(".var/app/org.vinegarhq.Sober/config/sober/config.json");
-            if !config_path.exists() {
-                return Err("Sober config not found".to_string());
-            }
-
-            let content = fs::read_to_string(&config_path).map_err(|e| e.to_string())?;
-            let mut value: serde_json::Value =
-                serde_json::from_str(&content).map_err(|e| e.to_string())?;
-
             let new_settings: serde_json::Value =
                 serde_json::from_str(&flags_json).map_err(|e| e.to_string())?;
-
-            if let Some(obj) = value.as_object_mut() {
-                if let Some(new_obj) = new_settings.as_object() {
-                    for (k, v) in new_obj {
-                        obj.insert(k.clone(), v.clone());
-                    }
-                }
-            } else {
-                return Err("Invalid Sober config structure".to_string());
-            }
-
-            let new_json = serde_json::to_string_pretty(&value).map_err(|e| e.to_string())?;
-            fs::write(&config_path, new_json).map_err(|e| e.to_string())?;
+            sober_config::update(app, &new_settings)?;
 
             Ok("Saved Sober settings".to_string())
         } else {
-            let config_path = home.join(".var/app/org.vinegarhq.Sober/config/sober/config.json");
-            if !config_path.exists() {
-                return Err("Sober config not found".to_string());
-            }
-
-            let content = fs::read_to_string(&config_path).map_err(|e| e.to_string())?;
-            let mut value: serde_json::Value =
-                serde_json::from_str(&content).map_err(|e| e.to_string())?;
-
             let new_flags: serde_json::Value =
                 serde_json::from_str(&flags_json).map_err(|e| e.to_string())?;
-
-            if let Some(obj) = value.as_object_mut() {
-                obj.insert("fflags".to_string(), new_flags);
-            } else {
-                return Err("Invalid Sober config structure".to_string());
-            }
-
-            let new_json = serde_json::to_string_pretty(&value).map_err(|e| e.to_string())?;
-            fs::write(&config_path, new_json).map_err(|e| e.to_string())?;
+            sober_config::update(app, &serde_json::json!({ "fflags": new_flags }))?;
 
             Ok("Saved to Sober config".to_string())
         }
@@ -596,6 +566,13 @@ fn show_progress_window(app: &tauri::AppHandle) {
     }
 }
 
+fn close_progress_window(app: &tauri::AppHandle) {
+    let _ = app.emit("progress-close", ());
+    if let Some(win) = app.get_webview_window("progress") {
+        let _ = win.close();
+    }
+}
+
 #[cfg(target_os = "macos")]
 fn apply_macos_fixes(path: &PathBuf) {
     use std::os::unix::fs::PermissionsExt;
@@ -633,30 +610,59 @@ fn apply_macos_fixes(path: &PathBuf) {
     }
 }
 
-fn download_and_install(
-    app: &tauri::AppHandle,
-    version: &str,
-    binary_type: &str,
-) -> Result<PathBuf, String> {
-    println!("[Downloader] Version: {}, Type: {}", version, binary_type);
-
-    #[cfg(target_os = "macos")]
-    if binary_type.contains("Studio") {
-        return Err("Studio not supported on macOS".to_string());
+fn exe_name_for(binary_type: &str) -> &'static str {
+    match binary_type {
+        "WindowsStudio" | "WindowsStudio64" => "RobloxStudioBeta.exe",
+        "WindowsPlayer" => "RobloxPlayerBeta.exe",
+        "MacStudio" => "RobloxStudio.app/Contents/MacOS/RobloxStudio",
+        "MacPlayer" => "RobloxPlayer.app/Contents/MacOS/RobloxPlayer",
+        _ => "RobloxPlayerBeta.exe",
     }
+}
 
-    if is_roblox_running() {
-        return Err(
-            "Roblox or Roblox Studio is currently running. Please close it before updating.".into(),
-        );
+fn installed_version_path(app: &tauri::AppHandle, binary_type: &str) -> Option<PathBuf> {
+    let versions_dir = storage::versions_dir(app).ok()?;
+    let exe_name = exe_name_for(binary_type);
+    fs::read_dir(versions_dir)
+        .ok()?
+        .flatten()
+        .map(|entry| entry.path())
+        .find(|path| path.join(exe_name).exists())
+}
+
+fn fetch_manifest_text(version: &str, binary_type: &str, name: &str) -> Result<String, String> {
+    let client = reqwest::blocking::Client::builder()
+        .user_agent("Roblox/WinInet")
+        .build()
+        .map_err(|e| e.to_string())?;
+    let url = format!("{}/{}-{}", cdn_url_prefix(binary_type), version, name);
+    let res = client.get(url).send().map_err(|e| e.to_string())?;
+    if !res.status().is_success() {
+        return Err(format!(
+            "Failed to fetch {} for {}: Status {}",
+            name,
+            version,
+            res.status()
+        ));
     }
+    res.text().map_err(|e| e.to_string())
+}
 
-    let data_dir = app.path().app_local_data_dir().map_err(|e| e.to_string())?;
-    let versions_dir = data_dir.join("rblx-versions");
-    println!("[Downloader] Target Directory: {}", versions_dir.display());
+fn cdn_url_prefix(binary_type: &str) -> &'static str {
+    if binary_type == "MacStudio" {
+        "https://setup.rbxcdn.com"
+    } else if binary_type.starts_with("Mac") {
+        "https://setup.rbxcdn.com/mac"
+    } else {
+        "https://setup.rbxcdn.com"
+    }
+}
 
+// Older versions are only removed once the new one is in place, so they can
+// donate unchanged files and a failed update never leaves nothing to launch.
+fn remove_stale_versions(versions_dir: &Path, version: &str, binary_type: &str) {
     if versions_dir.exists() {
-        if let Ok(entries) = fs::read_dir(&versions_dir) {
+        if let Ok(entries) = fs::read_dir(versions_dir) {
             for entry in entries.flatten() {
                 let path = entry.path();
                 if path.is_dir() {
@@ -684,14 +690,31 @@ fn download_and_install(
             }
         }
     }
+}
 
-    let exe_name = match binary_type {
-        "WindowsStudio" | "WindowsStudio64" => "RobloxStudioBeta.exe",
-        "WindowsPlayer" => "RobloxPlayerBeta.exe",
-        "MacStudio" => "RobloxStudio.app/Contents/MacOS/RobloxStudio",
-        "MacPlayer" => "RobloxPlayer.app/Contents/MacOS/RobloxPlayer",
-        _ => "RobloxPlayerBeta.exe",
-    };
+fn download_and_install(
+    app: &tauri::AppHandle,
+    version: &str,
+    binary_type: &str,
+    task: &tasks::TaskHandle,
+) -> Result<PathBuf, String> {
+    println!("[Downloader] Version: {}, Type: {}", version, binary_type);
+
+    #[cfg(target_os = "macos")]
+    if binary_type.contains("Studio") {
+        return Err("Studio not supported on macOS".to_string());
+    }
+
+    if is_roblox_running() {
+        return Err(
+            "Roblox or Roblox Studio is currently running. Please close it before updating.".into(),
+        );
+    }
+
+    let versions_dir = storage::versions_dir(app)?;
+    println!("[Downloader] Target Directory: {}", versions_dir.display());
+
+    let exe_name = exe_name_for(binary_type);
     let version_path = versions_dir.join(version);
     let exe_path = version_path.join(exe_name);
 
@@ -724,36 +747,103 @@ fn download_and_install(
             apply_macos_fixes(&app_path.to_path_buf());
         }
 
+        remove_stale_versions(&versions_dir, version, binary_type);
         return Ok(final_path);
     }
 
+    // A background prefetch may be fetching this very version; finishing it is
+    // quicker than starting the download over
+    tasks::wait_for(app, tasks::prefetch_key(binary_type), task)?;
+    let source = if updater::promote_staged_version(app, version, &version_path) {
+        println!("[Downloader] Using prefetched version {}", version);
+        "prefetch"
+    } else if adopt::import_matching_install(version, binary_type, &version_path, false, task)
+        .is_ok()
+    {
+        println!("[Downloader] Imported existing installation of {}", version);
+        "import"
+    } else {
+        task.check_cancelled()?;
+        if let Err(e) = fetch_version_files(app, version, binary_type, &version_path, Some(task)) {
+            // A half-extracted version would look installed on the next launch
+            let _ = fs::remove_dir_all(&version_path);
+            return Err(e);
+        }
+        "download"
+    };
+    metadata::record_install(app, version, binary_type, &version_path, source);
+    remove_stale_versions(&versions_dir, version, binary_type);
+    lan::prune_cache(app);
+
+    #[cfg(target_os = "windows")]
+    {
+        let settings_path = version_path.join("AppSettings.xml");
+        if !settings_path.exists() {
+            let settings_content = r#"<?xml version="1.0" encoding="UTF-8"?>
+<Settings>
+    <ContentFolder>content</ContentFolder>
+    <BaseUrl>http://www.roblox.com</BaseUrl>
+</Settings>"#;
+            let _ = fs::write(settings_path, settings_content);
+        }
+
+        if binary_type == "WindowsPlayer" {
+            let client_settings_path = version_path.join("ClientSettings");
+            if !client_settings_path.exists() {
+                let _ = fs::create_dir_all(client_settings_path);
+            }
+        }
+    }
+    let mut final_path = exe_path;
+    let path_str = final_path.to_string_lossy().to_string();
+    if path_str.starts_with(r"\\?\") {
+        final_path = PathBuf::from(&path_str[4..]);
+    }
+
+    #[cfg(target_os = "macos")]
+    apply_macos_fixes(&final_path);
+
+    #[cfg(all(target_family = "unix", not(target_os = "macos")))]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        if final_path.exists() {
+            if let Ok(metadata) = fs::metadata(&final_path) {
+                let mut perms = metadata.permissions();
+                perms.set_mode(0o755);
+                let _ = fs::set_permissions(&final_path, perms);
+            }
+        }
+    }
+
+    Ok(final_path)
+}
+
+fn fetch_version_files(
+    app: &tauri::AppHandle,
+    version: &str,
+    binary_type: &str,
+    version_path: &Path,
+    task: Option<&tasks::TaskHandle>,
+) -> Result<(), String> {
     let client = reqwest::blocking::Client::builder()
         .user_agent("Roblox/WinInet")
         .build()
         .map_err(|e| e.to_string())?;
 
-    let _ = app.emit(
-        "progress-update",
-        ProgressPayload {
-            status: "Starting download...".into(),
-            percent: 0,
-        },
-    );
+    if let Some(task) = task {
+        task.progress("Starting download...", 0);
+    }
 
     if !version_path.exists() {
-        let _ = fs::create_dir_all(&version_path);
+        let _ = fs::create_dir_all(version_path);
     }
 
     if binary_type == "MacStudio" {
         let dmg_name = "RobloxStudio.dmg";
         let dmg_url = "https://setup.rbxcdn.com/mac/RobloxStudio.dmg";
-        let _ = app.emit(
-            "progress-update",
-            ProgressPayload {
-                status: "Downloading Roblox Studio...".into(),
-                percent: 0,
-            },
-        );
+        if let Some(task) = task {
+            task.progress("Downloading Roblox Studio...", 0);
+        }
         let resp = client.get(dmg_url).send().map_err(|e| e.to_string())?;
         if !resp.status().is_success() {
             return Err(format!(
@@ -766,13 +856,9 @@ fn download_and_install(
         let temp_dir = std::env::temp_dir();
         let temp_path = temp_dir.join(dmg_name);
         fs::write(&temp_path, &content).map_err(|e| e.to_string())?;
-        let _ = app.emit(
-            "progress-update",
-            ProgressPayload {
-                status: "Mounting disk image...".into(),
-                percent: 50,
-            },
-        );
+        if let Some(task) = task {
+            task.progress("Mounting disk image...", 50);
+        }
         let attach_output = Command::new("hdiutil")
             .args(["attach", &temp_path.to_string_lossy()])
             .output()
@@ -792,7 +878,7 @@ fn download_and_install(
         let app_name = "RobloxStudio.app";
         let source_app = mount_path.join(&app_name);
         if source_app.exists() {
-            let _ = fs::create_dir_all(&version_path);
+            let _ = fs::create_dir_all(version_path);
             let dest_app = version_path.join(&app_name);
             let _ = Command::new("cp")
                 .args([
@@ -804,30 +890,20 @@ fn download_and_install(
         } else {
             return Err(format!("{} not found in dmg", app_name));
         }
-        let _ = app.emit(
-            "progress-update",
-            ProgressPayload {
-                status: "Cleaning up...".into(),
-                percent: 90,
-            },
-        );
+        if let Some(task) = task {
+            task.progress("Cleaning up...", 90);
+        }
         let _ = Command::new("hdiutil")
             .args(["detach", mount_point])
             .output();
         let _ = fs::remove_file(&temp_path);
     }
 
-    let url_prefix: &str;
-    if binary_type == "MacStudio" {
-        url_prefix = "https://setup.rbxcdn.com";
-    } else if binary_type.starts_with("Mac") {
-        url_prefix = "https://setup.rbxcdn.com/mac";
-    } else {
-        url_prefix = "https://setup.rbxcdn.com";
-    }
+    let url_prefix = cdn_url_prefix(binary_type);
 
     if binary_type != "MacStudio" {
         let mut packages_to_download = Vec::new();
+        let mut pkg_manifest = String::new();
 
         // Fetch manifests first to ensure Studio doesn't say "missing or corrupted"
         for m_name in ["rbxPkgManifest.txt", "rbxManifest.txt"] {
@@ -840,6 +916,7 @@ fn download_and_install(
 
                         if m_name == "rbxPkgManifest.txt" {
                             let text = String::from_utf8_lossy(&content);
+                            pkg_manifest = text.to_string();
                             let lines: Vec<&str> = text.lines().collect();
                             if !lines.is_empty() && lines[0] == "v0" {
                                 for i in (1..lines.len()).step_by(4) {
@@ -854,9 +931,20 @@ fn download_and_install(
             }
         }
 
+        let donors = dedup::DonorIndex::build(app, version_path);
+        let fetcher = PackageFetcher::new(
+            app,
+            &client,
+            url_prefix,
+            version,
+            &pkg_manifest,
+            task.map(|t| t.cancel_token()),
+        );
+
         // Fallback if manifest fetch failed
         if packages_to_download.is_empty() {
-            #[cfg(target_os = "windows")]
+            // Windows, and Studio running under Wine on Linux
+            #[cfg(not(target_os = "macos"))]
             {
                 let packages = if binary_type == "WindowsStudio" || binary_type == "WindowsStudio64"
                 {
@@ -928,6 +1016,13 @@ fn download_and_install(
             }
         }
 
+        let skipped = packages::skipped_packages(app, binary_type);
+        if !skipped.is_empty() {
+            println!("[Downloader] Skipping optional packages: {}", skipped.join(", "));
+            packages_to_download.retain(|pkg| !skipped.contains(pkg));
+        }
+
+        let mut failed = Vec::new();
         for (idx, pkg) in packages_to_download.iter().enumerate() {
             if pkg == "WebView2RuntimeInstaller.zip" {
                 continue;
@@ -938,248 +1033,316 @@ fn download_and_install(
 
             println!("[Downloader] Processing package: {} ({}%)", pkg, percent);
 
-            let _ = app.emit(
-                "progress-update",
-                ProgressPayload {
-                    status: format!("Updating {}...", label).into(),
-                    percent: percent as u64,
-                },
-            );
+            if let Some(task) = task {
+                task.progress(format!("Updating {}...", label), percent as u64);
+            }
 
-            let pkg_url = format!("{}/{}-{}", url_prefix, version, pkg);
-            let resp = match client.get(&pkg_url).send() {
-                Ok(r) => {
-                    if !r.status().is_success() {
-                        println!(
-                            "[Downloader] Failed to download {}: Status {}",
-                            pkg,
-                            r.status()
-                        );
-                        continue;
-                    }
-                    r
+            if let Err(e) = install_package(&fetcher, pkg, version_path, &donors) {
+                if let Some(task) = task {
+                    task.check_cancelled()?;
                 }
-                Err(e) => {
-                    println!("[Downloader] Request error for {}: {}", pkg, e);
-                    continue;
+                // The game cannot run without a required package, but optional
+                // ones can be added later from the packages list
+                if !packages::is_optional(binary_type, pkg) {
+                    return Err(e);
                 }
-            };
+                println!("[Downloader] Leaving out {}: {}", pkg, e);
+                failed.push(pkg.clone());
+            }
+        }
+        metadata::save_failed_packages(version_path, &failed);
+    }
 
-            let content = match resp.bytes() {
-                Ok(b) => b,
-                Err(e) => {
-                    println!("[Downloader] Failed to read bytes for {}: {}", pkg, e);
-                    continue;
-                }
-            };
+    Ok(())
+}
 
-            println!(
-                "[Downloader] Extracting {} ({} bytes)...",
-                pkg,
-                content.len()
-            );
-            let reader = Cursor::new(content);
-
-            let package_dir = match pkg.as_str() {
-                "RobloxStudio.zip" => "RobloxStudio.app",
-                "RobloxPlayer.zip" => "RobloxPlayer.app",
-                "RobloxApp.zip" | "Libraries.zip" | "LibrariesQt5.zip" | "redist.zip"
-                | "WebView2.zip" => "",
-                "shaders.zip" => "shaders",
-                "ssl.zip" => "ssl",
-                "content-avatar.zip" => "content/avatar",
-                "content-configs.zip" => "content/configs",
-                "content-fonts.zip" => "content/fonts",
-                "content-sky.zip" => "content/sky",
-                "content-sounds.zip" => "content/sounds",
-                "content-textures2.zip" => "content/textures",
-                "content-models.zip" => "content/models",
-                "content-textures3.zip" => "PlatformContent/pc/textures",
-                "content-terrain.zip" => "PlatformContent/pc/terrain",
-                "content-platform-fonts.zip" => "PlatformContent/pc/fonts",
-                "content-platform-dictionaries.zip" => {
-                    "PlatformContent/pc/shared_compression_dictionaries"
+fn package_extract_dir(pkg: &str) -> &'static str {
+    match pkg {
+        "RobloxStudio.zip" => "RobloxStudio.app",
+        "RobloxPlayer.zip" => "RobloxPlayer.app",
+        "RobloxApp.zip" | "Libraries.zip" | "LibrariesQt5.zip" | "redist.zip"
+        | "WebView2.zip" => "",
+        "shaders.zip" => "shaders",
+        "ssl.zip" => "ssl",
+        "content-avatar.zip" => "content/avatar",
+        "content-configs.zip" => "content/configs",
+        "content-fonts.zip" => "content/fonts",
+        "content-sky.zip" => "content/sky",
+        "content-sounds.zip" => "content/sounds",
+        "content-textures2.zip" => "content/textures",
+        "content-models.zip" => "content/models",
+        "content-textures3.zip" => "PlatformContent/pc/textures",
+        "content-terrain.zip" => "PlatformContent/pc/terrain",
+        "content-platform-fonts.zip" => "PlatformContent/pc/fonts",
+        "content-platform-dictionaries.zip" => {
+            "PlatformContent/pc/shared_compression_dictionaries"
+        }
+        "extracontent-luapackages.zip" => "ExtraContent/LuaPackages",
+        "extracontent-translations.zip" => "ExtraContent/translations",
+        "extracontent-models.zip" => "ExtraContent/models",
+        "extracontent-textures.zip" => "ExtraContent/textures",
+        "extracontent-places.zip" => "ExtraContent/places",
+        "content-studio_svg_textures.zip" => "content/studio_svg_textures",
+        "content-qt_translations.zip" => "content/qt_translations",
+        "content-api-docs.zip" => "content/api_docs",
+        "BuiltInPlugins.zip" => "BuiltInPlugins",
+        "BuiltInStandalonePlugins.zip" => "BuiltInStandalonePlugins",
+        "StudioFonts.zip" => "StudioFonts",
+        "ApplicationConfig.zip" => "ApplicationConfig",
+        "RibbonConfig.zip" => "RibbonConfig",
+        "Qml.zip" => "Qml",
+        "Plugins.zip" => "plugins",
+        _ => "",
+    }
+}
+
+// Downloads the packages of one version, trying LAN peers before the CDN.
+// Anything with an MD5 in rbxPkgManifest.txt is verified before use.
+struct PackageFetcher<'a> {
+    client: &'a reqwest::blocking::Client,
+    url_prefix: &'a str,
+    version: &'a str,
+    hashes: HashMap<String, String>,
+    peers: lan::PeerSource,
+    cancel: Option<tasks::CancelToken>,
+}
+
+impl<'a> PackageFetcher<'a> {
+    fn new(
+        app: &tauri::AppHandle,
+        client: &'a reqwest::blocking::Client,
+        url_prefix: &'a str,
+        version: &'a str,
+        pkg_manifest: &str,
+        cancel: Option<tasks::CancelToken>,
+    ) -> Self {
+        PackageFetcher {
+            client,
+            url_prefix,
+            version,
+            hashes: packages::parse_pkg_manifest(pkg_manifest)
+                .into_iter()
+                .map(|p| (p.name, p.md5))
+                .collect(),
+            peers: lan::PeerSource::new(app),
+            cancel,
+        }
+    }
+
+    fn check_cancelled(&self) -> Result<(), String> {
+        match &self.cancel {
+            Some(cancel) => cancel.check(),
+            None => Ok(()),
+        }
+    }
+
+    fn download(&self, pkg: &str) -> Result<Vec<u8>, String> {
+        self.check_cancelled()?;
+        let expected = self.hashes.get(pkg);
+        if let Some(md5) = expected {
+            if let Some(content) = self.peers.fetch(self.client, md5) {
+                self.peers.store(md5, &content);
+                return Ok(content);
+            }
+        }
+
+        let pkg_url = format!("{}/{}-{}", self.url_prefix, self.version, pkg);
+        let mut resp = match self.client.get(&pkg_url).send() {
+            Ok(r) => {
+                if !r.status().is_success() {
+                    return Err(format!("Failed to download {}: Status {}", pkg, r.status()));
                 }
-                "extracontent-luapackages.zip" => "ExtraContent/LuaPackages",
-                "extracontent-translations.zip" => "ExtraContent/translations",
-                "extracontent-models.zip" => "ExtraContent/models",
-                "extracontent-textures.zip" => "ExtraContent/textures",
-                "extracontent-places.zip" => "ExtraContent/places",
-                "content-studio_svg_textures.zip" => "content/studio_svg_textures",
-                "content-qt_translations.zip" => "content/qt_translations",
-                "content-api-docs.zip" => "content/api_docs",
-                "BuiltInPlugins.zip" => "BuiltInPlugins",
-                "BuiltInStandalonePlugins.zip" => "BuiltInStandalonePlugins",
-                "StudioFonts.zip" => "StudioFonts",
-                "ApplicationConfig.zip" => "ApplicationConfig",
-                "RibbonConfig.zip" => "RibbonConfig",
-                "Qml.zip" => "Qml",
-                "Plugins.zip" => "plugins",
-                _ => "",
-            };
+                r
+            }
+            Err(e) => {
+                return Err(format!("Request error for {}: {}", pkg, e));
+            }
+        };
 
-            if let Ok(mut archive) = ZipArchive::new(reader) {
-                for i in 0..archive.len() {
-                    if let Ok(mut file) = archive.by_index(i) {
-                        let name = file.name();
-                        if name.ends_with('/') {
-                            continue;
-                        }
+        // Read in chunks so a cancelled task stops mid-download
+        let mut content = Vec::new();
+        let mut buf = [0u8; 64 * 1024];
+        loop {
+            self.check_cancelled()?;
+            let n = resp
+                .read(&mut buf)
+                .map_err(|e| format!("Failed to read bytes for {}: {}", pkg, e))?;
+            if n == 0 {
+                break;
+            }
+            content.extend_from_slice(&buf[..n]);
+        }
+
+        if let Some(md5) = expected {
+            if &dedup::bytes_md5(&content) != md5 {
+                return Err(format!("{} failed MD5 verification", pkg));
+            }
+            self.peers.store(md5, &content);
+        }
+        Ok(content)
+    }
+}
 
-                        let mut entry_path = PathBuf::from(name);
+// Entry names in Roblox's zips use backslashes, which are not separators on
+// Linux. None when the converted path would leave the extract folder.
+fn zip_entry_path(name: &str) -> Option<PathBuf> {
+    let path = PathBuf::from(name.replace('\\', "/"));
+    path.components()
+        .all(|c| {
+            matches!(
+                c,
+                std::path::Component::Normal(_) | std::path::Component::CurDir
+            )
+        })
+        .then_some(path)
+}
 
-                        if !package_dir.is_empty() {
-                            let pkg_dir_path = PathBuf::from(package_dir.replace('\\', "/"));
-                            if entry_path.starts_with(&pkg_dir_path) {
-                                if let Ok(remaining) = entry_path.strip_prefix(&pkg_dir_path) {
-                                    entry_path = remaining.to_path_buf();
-                                }
-                            } else {
-                                if let Some(first_comp) = entry_path.components().next() {
-                                    if let std::path::Component::Normal(c1) = first_comp {
-                                        if let Some(p_first_comp) = pkg_dir_path.components().next()
+fn install_package(
+    fetcher: &PackageFetcher,
+    pkg: &str,
+    version_path: &Path,
+    donors: &dedup::DonorIndex,
+) -> Result<(), String> {
+    let content = fetcher.download(pkg)?;
+
+    println!(
+        "[Downloader] Extracting {} ({} bytes)...",
+        pkg,
+        content.len()
+    );
+    let reader = Cursor::new(content);
+
+    let package_dir = package_extract_dir(pkg);
+
+    if let Ok(mut archive) = ZipArchive::new(reader) {
+        for i in 0..archive.len() {
+            fetcher.check_cancelled()?;
+            if let Ok(mut file) = archive.by_index(i) {
+                if file.is_dir() {
+                    continue;
+                }
+
+                let mut entry_path = file
+                    .enclosed_name()
+                    .and_then(|_| zip_entry_path(file.name()))
+                    .ok_or(format!("{} contains an unsafe path: {}", pkg, file.name()))?;
+
+                if !package_dir.is_empty() {
+                    let pkg_dir_path = PathBuf::from(package_dir.replace('\\', "/"));
+                    if entry_path.starts_with(&pkg_dir_path) {
+                        if let Ok(remaining) = entry_path.strip_prefix(&pkg_dir_path) {
+                            entry_path = remaining.to_path_buf();
+                        }
+                    } else {
+                        if let Some(first_comp) = entry_path.components().next() {
+                            if let std::path::Component::Normal(c1) = first_comp {
+                                if let Some(p_first_comp) = pkg_dir_path.components().next()
+                                {
+                                    if let std::path::Component::Normal(p1) = p_first_comp {
+                                        if c1.to_string_lossy().to_lowercase()
+                                            == p1.to_string_lossy().to_lowercase()
                                         {
-                                            if let std::path::Component::Normal(p1) = p_first_comp {
-                                                if c1.to_string_lossy().to_lowercase()
-                                                    == p1.to_string_lossy().to_lowercase()
+                                            let entry_comps: Vec<_> =
+                                                entry_path.components().collect();
+                                            let pkg_comps: Vec<_> =
+                                                pkg_dir_path.components().collect();
+                                            let mut match_count = 0;
+                                            for (ec, pc) in
+                                                entry_comps.iter().zip(pkg_comps.iter())
+                                            {
+                                                if ec
+                                                    .as_os_str()
+                                                    .to_string_lossy()
+                                                    .to_lowercase()
+                                                    == pc
+                                                        .as_os_str()
+                                                        .to_string_lossy()
+                                                        .to_lowercase()
                                                 {
-                                                    let entry_comps: Vec<_> =
-                                                        entry_path.components().collect();
-                                                    let pkg_comps: Vec<_> =
-                                                        pkg_dir_path.components().collect();
-                                                    let mut match_count = 0;
-                                                    for (ec, pc) in
-                                                        entry_comps.iter().zip(pkg_comps.iter())
-                                                    {
-                                                        if ec
-                                                            .as_os_str()
-                                                            .to_string_lossy()
-                                                            .to_lowercase()
-                                                            == pc
-                                                                .as_os_str()
-                                                                .to_string_lossy()
-                                                                .to_lowercase()
-                                                        {
-                                                            match_count += 1;
-                                                        } else {
-                                                            break;
-                                                        }
-                                                    }
-                                                    if match_count > 0 {
-                                                        let mut p = PathBuf::new();
-                                                        for comp in entry_comps
-                                                            .into_iter()
-                                                            .skip(match_count)
-                                                        {
-                                                            p.push(comp.as_os_str());
-                                                        }
-                                                        entry_path = p;
-                                                    }
+                                                    match_count += 1;
+                                                } else {
+                                                    break;
                                                 }
                                             }
+                                            if match_count > 0 {
+                                                let mut p = PathBuf::new();
+                                                for comp in entry_comps
+                                                    .into_iter()
+                                                    .skip(match_count)
+                                                {
+                                                    p.push(comp.as_os_str());
+                                                }
+                                                entry_path = p;
+                                            }
                                         }
                                     }
                                 }
                             }
                         }
+                    }
+                }
 
-                        if entry_path.as_os_str().is_empty() {
-                            continue;
-                        }
-
-                        let target_dir = version_path.join(package_dir);
-                        let outpath = target_dir.join(&entry_path);
+                if entry_path.as_os_str().is_empty() {
+                    continue;
+                }
 
-                        if let Some(p) = outpath.parent() {
-                            let _ = fs::create_dir_all(p);
-                        }
+                let target_dir = version_path.join(package_dir);
+                let outpath = target_dir.join(&entry_path);
 
-                        #[cfg(unix)]
-                        let is_symlink =
-                            file.unix_mode().map(|m| (m >> 12) == 0o12).unwrap_or(false);
-                        #[cfg(not(unix))]
-                        let is_symlink = false;
-
-                        if is_symlink {
-                            #[cfg(unix)]
-                            {
-                                use std::io::Read;
-                                let mut link_to = String::new();
-                                if file.read_to_string(&mut link_to).is_ok() {
-                                    let _ = fs::remove_file(&outpath);
-                                    let _ = std::os::unix::fs::symlink(link_to.trim(), &outpath);
-                                }
-                            }
-                        } else {
-                            if let Ok(mut outfile) = fs::File::create(&outpath) {
-                                let _ = std::io::copy(&mut file, &mut outfile);
-                            }
-                        }
+                if let Some(p) = outpath.parent() {
+                    let _ = fs::create_dir_all(p);
+                }
 
-                        #[cfg(unix)]
-                        {
-                            use std::os::unix::fs::PermissionsExt;
-                            if let Some(mode) = file.unix_mode() {
-                                let _ =
-                                    fs::set_permissions(&outpath, fs::Permissions::from_mode(mode));
-                            }
+                #[cfg(unix)]
+                let is_symlink =
+                    file.unix_mode().map(|m| (m >> 12) == 0o12).unwrap_or(false);
+                #[cfg(not(unix))]
+                let is_symlink = false;
+
+                if is_symlink {
+                    #[cfg(unix)]
+                    {
+                        let mut link_to = String::new();
+                        if file.read_to_string(&mut link_to).is_ok() {
+                            let _ = fs::remove_file(&outpath);
+                            let _ = std::os::unix::fs::symlink(link_to.trim(), &outpath);
                         }
                     }
+                } else if !donors.link(
+                    &Path::new(package_dir).join(&entry_path),
+                    &outpath,
+                    file.size(),
+                ) {
+                    let mut outfile = fs::File::create(&outpath)
+                        .map_err(|e| format!("Failed to write {}: {}", outpath.display(), e))?;
+                    std::io::copy(&mut file, &mut outfile)
+                        .map_err(|e| format!("Failed to extract {}: {}", outpath.display(), e))?;
                 }
-            } else {
-                println!("[Downloader] Failed to open zip archive for {}", pkg);
-            }
-        }
-    }
-
-    #[cfg(target_os = "windows")]
-    {
-        let settings_path = version_path.join("AppSettings.xml");
-        if !settings_path.exists() {
-            let settings_content = r#"<?xml version="1.0" encoding="UTF-8"?>
-<Settings>
-    <ContentFolder>content</ContentFolder>
-    <BaseUrl>http://www.roblox.com</BaseUrl>
-</Settings>"#;
-            let _ = fs::write(settings_path, settings_content);
-        }
-
-        if binary_type == "WindowsPlayer" {
-            let client_settings_path = version_path.join("ClientSettings");
-            if !client_settings_path.exists() {
-                let _ = fs::create_dir_all(client_settings_path);
-            }
-        }
-    }
-    let mut final_path = exe_path;
-    let path_str = final_path.to_string_lossy().to_string();
-    if path_str.starts_with(r"\\?\") {
-        final_path = PathBuf::from(&path_str[4..]);
-    }
-
-    #[cfg(target_os = "macos")]
-    apply_macos_fixes(&final_path);
 
-    #[cfg(all(target_family = "unix", not(target_os = "macos")))]
-    {
-        use std::os::unix::fs::PermissionsExt;
-        if final_path.exists() {
-            if let Ok(metadata) = fs::metadata(&final_path) {
-                let mut perms = metadata.permissions();
-                perms.set_mode(0o755);
-                let _ = fs::set_permissions(&final_path, perms);
+                #[cfg(unix)]
+                {
+                    use std::os::unix::fs::PermissionsExt;
+                    if let Some(mode) = file.unix_mode() {
+                        let _ =
+                            fs::set_permissions(&outpath, fs::Permissions::from_mode(mode));
+                    }
+                }
             }
         }
+    } else {
+        return Err(format!("Failed to open zip archive for {}", pkg));
     }
 
-    Ok(final_path)
+    Ok(())
 }
 
+#[cfg(target_os = "windows")]
 fn install_mods(
+    app: &tauri::AppHandle,
     version_path: &PathBuf,
     flags_json: String,
     skybox_path: String,
-) -> Result<(), String> {
+) {
     if !flags_json.is_empty() && flags_json != "{}" {
         let client_settings = version_path.join("ClientSettings");
         if !client_settings.exists() {
@@ -1188,52 +1351,25 @@ fn install_mods(
         let _ = fs::write(client_settings.join("ClientAppSettings.json"), &flags_json);
     }
 
+    // Each mod is independent, so one failing must not keep the others off
     if !skybox_path.is_empty() {
-        let sky_dir = version_path
-            .join("PlatformContent")
-            .join("pc")
-            .join("textures")
-            .join("sky");
-        if sky_dir.exists() {
-            let source_path = PathBuf::from(&skybox_path);
-            if source_path.exists() {
-                let copy_if_tex =
-                    |entry_path: PathBuf, target_folder: &PathBuf| -> Result<(), std::io::Error> {
-                        if let Some(name) = entry_path.file_name() {
-                            let name_str = name.to_string_lossy().to_lowercase();
-                            if name_str.ends_with(".tex")
-                                || name_str.ends_with(".png")
-                                || name_str.ends_with(".jpg")
-                            {
-                                fs::copy(&entry_path, target_folder.join(name))?;
-                            }
-                        }
-                        Ok(())
-                    };
-
-                if source_path.is_dir() {
-                    if let Ok(entries) = fs::read_dir(&source_path) {
-                        for entry in entries.flatten() {
-                            let _ = copy_if_tex(entry.path(), &sky_dir);
-                        }
-                    }
-                }
-            }
+        if let Err(e) = skybox::apply_to_version(app, version_path, &skybox_path) {
+            println!("[Skybox] Failed to apply {}: {}", skybox_path, e);
         }
     }
-    Ok(())
+    if let Err(e) = mods::apply_to_version(app, version_path) {
+        println!("[Mods] Failed to apply modifications: {}", e);
+    }
 }
 
 #[tauri::command]
-async fn ensure_roblox_installed(app: tauri::AppHandle) -> Result<String, String> {
+async fn ensure_roblox_installed(
+    app: tauri::AppHandle,
+    on_progress: Channel<tasks::TaskEvent>,
+) -> Result<String, String> {
+    let task = tasks::start(&app, "Installing Roblox", "install:player", on_progress)?;
     show_progress_window(&app);
-    let _ = app.emit(
-        "progress-update",
-        ProgressPayload {
-            status: "Checking for updates...".into(),
-            percent: 0,
-        },
-    );
+    task.progress("Checking for updates...", 0);
 
     let app_clone = app.clone();
     let res = tauri::async_runtime::spawn_blocking(move || {
@@ -1242,117 +1378,36 @@ async fn ensure_roblox_installed(app: tauri::AppHandle) -> Result<String, String
         #[cfg(not(target_os = "macos"))]
         let binary_type = "WindowsPlayer";
 
-        let version = get_latest_version(binary_type)?;
-        download_and_install(&app_clone, &version, binary_type)
+        let res = get_latest_version(binary_type)
+            .and_then(|version| download_and_install(&app_clone, &version, binary_type, &task));
+        task.finish(&res);
+        res
     })
     .await
-    .map_err(|e| e.to_string())??;
+    .map_err(|e| e.to_string())?;
 
-    let mut path_str = res.to_string_lossy().to_string();
+    close_progress_window(&app);
+
+    let mut path_str = res?.to_string_lossy().to_string();
     if path_str.starts_with(r"\\?\") {
         path_str = path_str[4..].to_string();
     }
 
-    let _ = app.emit("progress-close", ());
-    if let Some(win) = app.get_webview_window("progress") {
-        let _ = win.close();
-    }
-
     Ok(path_str)
 }
 
 #[tauri::command]
-async fn launch_roblox_executable(path: String) -> Result<(), String> {
+#[allow(unused_variables)]
+async fn launch_roblox_executable(app: tauri::AppHandle, path: String) -> Result<(), String> {
     if path == "sober" {
         #[cfg(any(target_os = "windows", target_os = "linux"))]
         {
             #[cfg(target_os = "linux")]
             {
-                // Helper to test if a command exists
-                fn command_exists(cmd: &str) -> bool {
-                    Command::new("sh")
-                        .arg("-c")
-                        .arg(format!("command -v {} >/dev/null 2>&1", cmd))
-                        .status()
-                        .map(|s| s.success())
-                        .unwrap_or(false)
+                if let Err(e) = mods::apply_to_sober(&app) {
+                    println!("[Mods] Failed to apply modifications: {}", e);
                 }
-
-                // Try to install flatpak if missing
-                fn try_install_flatpak() -> Result<(), String> {
-                    if command_exists("flatpak") {
-                        return Ok(());
-                    }
-
-                    let installers = [
-                        ("apt-get", "sudo apt-get update && sudo apt-get install -y flatpak"),
-                        ("dnf", "sudo dnf install -y flatpak"),
-                        ("pacman", "sudo pacman -S --noconfirm flatpak"),
-                        ("zypper", "sudo zypper install -y flatpak"),
-                    ];
-
-                    for (mgr, cmd) in installers {
-                        if command_exists(mgr) {
-                            let status = Command::new("sh").arg("-c").arg(cmd).status().map_err(|e| e.to_string())?;
-                            if status.success() && command_exists("flatpak") {
-                                return Ok(());
-                            }
-                        }
-                    }
-
-                    Err("Failed to install flatpak: no supported package manager found or installation failed".into())
-                }
-
-                // Ensure flathub remote exists
-                fn ensure_flathub_remote() -> Result<(), String> {
-                    let check = Command::new("sh")
-                        .arg("-c")
-                        .arg("flatpak remote-list | awk '{print $1}' | grep -x flathub >/dev/null 2>&1")
-                        .status()
-                        .map_err(|e| e.to_string())?;
-                    if check.success() {
-                        return Ok(());
-                    }
-                    let add = Command::new("flatpak")
-                        .args(["remote-add", "--if-not-exists", "flathub", "https://flathub.org/repo/flathub.flatpakrepo"])
-                        .status()
-                        .map_err(|e| e.to_string())?;
-                    if add.success() {
-                        Ok(())
-                    } else {
-                        Err("Failed to add flathub remote".into())
-                    }
-                }
-
-                fn ensure_flatpak_app(pkg: &str) -> Result<(), String> {
-                    // Check installed apps
-                    let list = Command::new("flatpak")
-                        .args(["list", "--app", "--columns=application"])
-                        .output()
-                        .map_err(|e| e.to_string())?;
-
-                    let stdout = String::from_utf8_lossy(&list.stdout);
-                    if stdout.lines().any(|l| l.trim() == pkg) {
-                        return Ok(());
-                    }
-
-                    ensure_flathub_remote()?;
-
-                    let install_cmd = format!("flatpak install -y flathub {}", pkg);
-                    let status = Command::new("sh").arg("-c").arg(&install_cmd).status().map_err(|e| e.to_string())?;
-                    if status.success() {
-                        Ok(())
-                    } else {
-                        Err(format!("Failed to install {} via flatpak", pkg))
-                    }
-                }
-
-                try_install_flatpak()?;
-                ensure_flatpak_app("org.vinegarhq.Sober")?;
-                Command::new("flatpak")
-                    .args(["run", "org.vinegarhq.Sober"])
-                    .spawn()
-                    .map_err(|e| e.to_string())?;
+                linux_apps::ensure(&app, flatpak::SOBER_APP_ID, None)?.launch()?;
                 return Ok(());
             }
 
@@ -1387,6 +1442,22 @@ async fn launch_roblox_executable(path: String) -> Result<(), String> {
     {
         let version_dir = exe_path.parent().ok_or("Invalid path")?;
 
+        let is_player = exe_path
+            .file_name()
+            .is_some_and(|name| name.eq_ignore_ascii_case("RobloxPlayerBeta.exe"));
+        if is_player {
+            let app_clone = app.clone();
+            let mods_dir = version_dir.to_path_buf();
+            let applied = tauri::async_runtime::spawn_blocking(move || {
+                mods::apply_to_version(&app_clone, &mods_dir)
+            })
+            .await
+            .map_err(|e| e.to_string())?;
+            if let Err(e) = applied {
+                println!("[Mods] Failed to apply modifications: {}", e);
+            }
+        }
+
         let exe_str = exe_path.to_string_lossy();
         let dir_str = version_dir.to_string_lossy();
 
@@ -1413,88 +1484,13 @@ async fn launch_roblox_executable(path: String) -> Result<(), String> {
 
     #[cfg(target_os = "linux")]
     {
-        if path.contains("org.vinegarhq.Sober") || path.contains("org.vinegarhq.Vinegar") {
-            let pkg = if path.contains("org.vinegarhq.Sober") {
-                "org.vinegarhq.Sober"
+        if path.contains(flatpak::SOBER_APP_ID) || path.contains(flatpak::VINEGAR_APP_ID) {
+            let pkg = if path.contains(flatpak::SOBER_APP_ID) {
+                flatpak::SOBER_APP_ID
             } else {
-                "org.vinegarhq.Vinegar"
+                flatpak::VINEGAR_APP_ID
             };
-
-            fn command_exists(cmd: &str) -> bool {
-                Command::new("sh")
-                    .arg("-c")
-                    .arg(format!("command -v {} >/dev/null 2>&1", cmd))
-                    .status()
-                    .map(|s| s.success())
-                    .unwrap_or(false)
-            }
-
-            fn try_install_flatpak() -> Result<(), String> {
-                if command_exists("flatpak") {
-                    return Ok(());
-                }
-                let installers = [
-                    ("apt-get", "sudo apt-get update && sudo apt-get install -y flatpak"),
-                    ("dnf", "sudo dnf install -y flatpak"),
-                    ("pacman", "sudo pacman -S --noconfirm flatpak"),
-                    ("zypper", "sudo zypper install -y flatpak"),
-                ];
-                for (_mgr, cmd) in installers {
-                    if command_exists(_mgr) {
-                        let status = Command::new("sh").arg("-c").arg(cmd).status().map_err(|e| e.to_string())?;
-                        if status.success() && command_exists("flatpak") {
-                            return Ok(());
-                        }
-                    }
-                }
-                Err("Failed to install flatpak: no supported package manager found or installation failed".into())
-            }
-
-            fn ensure_flathub_remote() -> Result<(), String> {
-                let check = Command::new("sh")
-                    .arg("-c")
-                    .arg("flatpak remote-list | awk '{print $1}' | grep -x flathub >/dev/null 2>&1")
-                    .status()
-                    .map_err(|e| e.to_string())?;
-                if check.success() {
-                    return Ok(());
-                }
-                let add = Command::new("flatpak")
-                    .args(["remote-add", "--if-not-exists", "flathub", "https://flathub.org/repo/flathub.flatpakrepo"])
-                    .status()
-                    .map_err(|e| e.to_string())?;
-                if add.success() {
-                    Ok(())
-                } else {
-                    Err("Failed to add flathub remote".into())
-                }
-            }
-
-            fn ensure_flatpak_app(pkg: &str) -> Result<(), String> {
-                let list = Command::new("flatpak")
-                    .args(["list", "--app", "--columns=application"])
-                    .output()
-                    .map_err(|e| e.to_string())?;
-                let stdout = String::from_utf8_lossy(&list.stdout);
-                if stdout.lines().any(|l| l.trim() == pkg) {
-                    return Ok(());
-                }
-                ensure_flathub_remote()?;
-                let install_cmd = format!("flatpak install -y flathub {}", pkg);
-                let status = Command::new("sh").arg("-c").arg(&install_cmd).status().map_err(|e| e.to_string())?;
-                if status.success() {
-                    Ok(())
-                } else {
-                    Err(format!("Failed to install {} via flatpak", pkg))
-                }
-            }
-
-            try_install_flatpak()?;
-            ensure_flatpak_app(pkg)?;
-            Command::new("flatpak")
-                .args(["run", pkg])
-                .spawn()
-                .map_err(|e| e.to_string())?;
+            linux_apps::ensure(&app, pkg, None)?.launch()?;
         } else {
             Command::new(&exe_path).spawn().map_err(|e| e.to_string())?;
         }
@@ -1509,15 +1505,11 @@ async fn launch_roblox(
     app: tauri::AppHandle,
     flags_json: String,
     skybox_path: String,
+    on_progress: Channel<tasks::TaskEvent>,
 ) -> Result<(), String> {
+    let task = tasks::start(&app, "Launching Roblox", "install:player", on_progress)?;
     show_progress_window(&app);
-    let _ = app.emit(
-        "progress-update",
-        ProgressPayload {
-            status: "Checking for updates...".into(),
-            percent: 0,
-        },
-    );
+    task.progress("Checking for updates...", 0);
 
     let app_clone = app.clone();
 
@@ -1526,25 +1518,14 @@ async fn launch_roblox(
             #[cfg(target_os = "windows")]
             {
                 let version = get_latest_version("WindowsPlayer")?;
-                let _ = app_clone.emit(
-                    "progress-update",
-                    ProgressPayload {
-                        status: "Verifying installation...".into(),
-                        percent: 0,
-                    },
-                );
-                let exe_path = download_and_install(&app_clone, &version, "WindowsPlayer")?;
+                task.progress("Verifying installation...", 0);
+                let exe_path = download_and_install(&app_clone, &version, "WindowsPlayer", &task)?;
 
                 let version_dir = exe_path.parent().unwrap().to_path_buf();
-                let _ = install_mods(&version_dir, flags_json, skybox_path);
+                task.progress("Applying mods...", 100);
+                install_mods(&app_clone, &version_dir, flags_json, skybox_path);
 
-                let _ = app_clone.emit(
-                    "progress-update",
-                    ProgressPayload {
-                        status: "Launching...".into(),
-                        percent: 100,
-                    },
-                );
+                task.progress("Launching...", 100);
 
                 let exe_str = exe_path.to_string_lossy().to_string();
 
@@ -1560,110 +1541,21 @@ async fn launch_roblox(
             }
             #[cfg(target_os = "linux")]
             {
-                let _ = app_clone.emit(
-                    "progress-update",
-                    ProgressPayload {
-                        status: "Launching Sober...".into(),
-                        percent: 100,
-                    },
-                );
-
-                // Helpers (scoped locally) to detect and install flatpak and the app
-                fn command_exists(cmd: &str) -> bool {
-                    Command::new("sh")
-                        .arg("-c")
-                        .arg(format!("command -v {} >/dev/null 2>&1", cmd))
-                        .status()
-                        .map(|s| s.success())
-                        .unwrap_or(false)
-                }
-
-                fn try_install_flatpak() -> Result<(), String> {
-                    if command_exists("flatpak") {
-                        return Ok(());
-                    }
-                    let installers = [
-                        ("apt-get", "sudo apt-get update && sudo apt-get install -y flatpak"),
-                        ("dnf", "sudo dnf install -y flatpak"),
-                        ("pacman", "sudo pacman -S --noconfirm flatpak"),
-                        ("zypper", "sudo zypper install -y flatpak"),
-                    ];
-                    for (_mgr, cmd) in installers {
-                        if command_exists(_mgr) {
-                            let status = Command::new("sh").arg("-c").arg(cmd).status().map_err(|e| e.to_string())?;
-                            if status.success() && command_exists("flatpak") {
-                                return Ok(());
-                            }
-                        }
-                    }
-                    Err("Failed to install flatpak: no supported package manager found or installation failed".into())
-                }
-
-                fn ensure_flathub_remote() -> Result<(), String> {
-                    let check = Command::new("sh")
-                        .arg("-c")
-                        .arg("flatpak remote-list | awk '{print $1}' | grep -x flathub >/dev/null 2>&1")
-                        .status()
-                        .map_err(|e| e.to_string())?;
-                    if check.success() {
-                        return Ok(());
-                    }
-                    let add = Command::new("flatpak")
-                        .args(["remote-add", "--if-not-exists", "flathub", "https://flathub.org/repo/flathub.flatpakrepo"])
-                        .status()
-                        .map_err(|e| e.to_string())?;
-                    if add.success() {
-                        Ok(())
-                    } else {
-                        Err("Failed to add flathub remote".into())
-                    }
+                let sober = linux_apps::ensure(&app_clone, flatpak::SOBER_APP_ID, Some(&task))?;
+                task.progress("Applying modifications...", 100);
+                if let Err(e) = mods::apply_to_sober(&app_clone) {
+                    println!("[Mods] Failed to apply modifications: {}", e);
                 }
-
-                fn ensure_flatpak_app(pkg: &str) -> Result<(), String> {
-                    let list = Command::new("flatpak")
-                        .args(["list", "--app", "--columns=application"])
-                        .output()
-                        .map_err(|e| e.to_string())?;
-                    let stdout = String::from_utf8_lossy(&list.stdout);
-                    if stdout.lines().any(|l| l.trim() == pkg) {
-                        return Ok(());
-                    }
-                    ensure_flathub_remote()?;
-                    let install_cmd = format!("flatpak install -y flathub {}", pkg);
-                    let status = Command::new("sh").arg("-c").arg(&install_cmd).status().map_err(|e| e.to_string())?;
-                    if status.success() {
-                        Ok(())
-                    } else {
-                        Err(format!("Failed to install {} via flatpak", pkg))
-                    }
-                }
-
-                try_install_flatpak()?;
-                ensure_flatpak_app("org.vinegarhq.Sober")?;
-                Command::new("flatpak")
-                    .args(["run", "org.vinegarhq.Sober"])
-                    .spawn()
-                    .map_err(|e| e.to_string())?;
+                task.progress("Launching Sober...", 100);
+                sober.launch()?;
                 Ok(())
             }
             #[cfg(target_os = "macos")]
             {
                 let version = get_latest_version("MacPlayer")?;
-                let _ = app_clone.emit(
-                    "progress-update",
-                    ProgressPayload {
-                        status: "Verifying installation...".into(),
-                        percent: 0,
-                    },
-                );
-                let exe_path = download_and_install(&app_clone, &version, "MacPlayer")?;
-                let _ = app_clone.emit(
-                    "progress-update",
-                    ProgressPayload {
-                        status: "Launching...".into(),
-                        percent: 100,
-                    },
-                );
+                task.progress("Verifying installation...", 0);
+                let exe_path = download_and_install(&app_clone, &version, "MacPlayer", &task)?;
+                task.progress("Launching...", 100);
                 let app_bundle = exe_path
                     .parent()
                     .unwrap()
@@ -1683,11 +1575,8 @@ async fn launch_roblox(
             }
         })();
 
-        std::thread::sleep(std::time::Duration::from_millis(1500));
-        let _ = app_clone.emit("progress-close", ());
-        if let Some(win) = app_clone.get_webview_window("progress") {
-            let _ = win.close();
-        }
+        task.finish(&res);
+        close_progress_window(&app_clone);
 
         res
     })
@@ -1698,15 +1587,13 @@ async fn launch_roblox(
 }
 
 #[tauri::command]
-async fn launch_studio(app: tauri::AppHandle) -> Result<(), String> {
+async fn launch_studio(
+    app: tauri::AppHandle,
+    on_progress: Channel<tasks::TaskEvent>,
+) -> Result<(), String> {
+    let task = tasks::start(&app, "Launching Studio", "install:studio", on_progress)?;
     show_progress_window(&app);
-    let _ = app.emit(
-        "progress-update",
-        ProgressPayload {
-            status: "Checking for updates...".into(),
-            percent: 0,
-        },
-    );
+    task.progress("Checking for updates...", 0);
 
     let app_clone = app.clone();
     tauri::async_runtime::spawn_blocking(move || {
@@ -1715,22 +1602,10 @@ async fn launch_studio(app: tauri::AppHandle) -> Result<(), String> {
             {
                 let binary_type = "WindowsStudio64";
                 let version = get_latest_version(binary_type)?;
-                let _ = app_clone.emit(
-                    "progress-update",
-                    ProgressPayload {
-                        status: "Verifying installation...".into(),
-                        percent: 0,
-                    },
-                );
-                let exe_path = download_and_install(&app_clone, &version, binary_type)?;
-
-                let _ = app_clone.emit(
-                    "progress-update",
-                    ProgressPayload {
-                        status: "Launching Studio...".into(),
-                        percent: 100,
-                    },
-                );
+                task.progress("Verifying installation...", 0);
+                let exe_path = download_and_install(&app_clone, &version, binary_type, &task)?;
+
+                task.progress("Launching Studio...", 100);
 
                 let version_dir = exe_path.parent().unwrap();
                 let mut final_exe = exe_path.clone();
@@ -1755,90 +1630,13 @@ async fn launch_studio(app: tauri::AppHandle) -> Result<(), String> {
             }
             #[cfg(target_os = "linux")]
             {
-                let _ = app_clone.emit(
-                    "progress-update",
-                    ProgressPayload {
-                        status: "Launching Vinegar...".into(),
-                        percent: 100,
-                    },
-                );
-
-                // Local helpers to ensure flatpak and Vinegar are installed
-                fn command_exists(cmd: &str) -> bool {
-                    Command::new("sh")
-                        .arg("-c")
-                        .arg(format!("command -v {} >/dev/null 2>&1", cmd))
-                        .status()
-                        .map(|s| s.success())
-                        .unwrap_or(false)
-                }
-
-                fn try_install_flatpak() -> Result<(), String> {
-                    if command_exists("flatpak") {
-                        return Ok(());
-                    }
-                    let installers = [
-                        ("apt-get", "sudo apt-get update && sudo apt-get install -y flatpak"),
-                        ("dnf", "sudo dnf install -y flatpak"),
-                        ("pacman", "sudo pacman -S --noconfirm flatpak"),
-                        ("zypper", "sudo zypper install -y flatpak"),
-                    ];
-                    for (mgr, cmd) in installers {
-                        if command_exists(mgr) {
-                            let status = Command::new("sh").arg("-c").arg(cmd).status().map_err(|e| e.to_string())?;
-                            if status.success() && command_exists("flatpak") {
-                                return Ok(());
-                            }
-                        }
-                    }
-                    Err("Failed to install flatpak: no supported package manager found or installation failed".into())
+                if settings::load(&app_clone).studio_backend == settings::StudioBackend::Wine {
+                    return wine::launch_studio(&app_clone, &task);
                 }
-
-                fn ensure_flathub_remote() -> Result<(), String> {
-                    let check = Command::new("sh")
-                        .arg("-c")
-                        .arg("flatpak remote-list | awk '{print $1}' | grep -x flathub >/dev/null 2>&1")
-                        .status()
-                        .map_err(|e| e.to_string())?;
-                    if check.success() {
-                        return Ok(());
-                    }
-                    let add = Command::new("flatpak")
-                        .args(["remote-add", "--if-not-exists", "flathub", "https://flathub.org/repo/flathub.flatpakrepo"])
-                        .status()
-                        .map_err(|e| e.to_string())?;
-                    if add.success() {
-                        Ok(())
-                    } else {
-                        Err("Failed to add flathub remote".into())
-                    }
-                }
-
-                fn ensure_flatpak_app(pkg: &str) -> Result<(), String> {
-                    let list = Command::new("flatpak")
-                        .args(["list", "--app", "--columns=application"])
-                        .output()
-                        .map_err(|e| e.to_string())?;
-                    let stdout = String::from_utf8_lossy(&list.stdout);
-                    if stdout.lines().any(|l| l.trim() == pkg) {
-                        return Ok(());
-                    }
-                    ensure_flathub_remote()?;
-                    let install_cmd = format!("flatpak install -y flathub {}", pkg);
-                    let status = Command::new("sh").arg("-c").arg(&install_cmd).status().map_err(|e| e.to_string())?;
-                    if status.success() {
-                        Ok(())
-                    } else {
-                        Err(format!("Failed to install {} via flatpak", pkg))
-                    }
-                }
-
-                try_install_flatpak()?;
-                ensure_flatpak_app("org.vinegarhq.Vinegar")?;
-                Command::new("flatpak")
-                    .args(["run", "org.vinegarhq.Vinegar"])
-                    .spawn()
-                    .map_err(|e| e.to_string())?;
+                let vinegar =
+                    linux_apps::ensure(&app_clone, flatpak::VINEGAR_APP_ID, Some(&task))?;
+                task.progress("Launching Vinegar...", 100);
+                vinegar.launch()?;
                 Ok(())
             }
             #[cfg(target_os = "macos")]
@@ -1851,11 +1649,8 @@ async fn launch_studio(app: tauri::AppHandle) -> Result<(), String> {
             }
         })();
 
-        std::thread::sleep(std::time::Duration::from_millis(1500));
-        let _ = app_clone.emit("progress-close", ());
-        if let Some(win) = app_clone.get_webview_window("progress") {
-            let _ = win.close();
-        }
+        task.finish(&res);
+        close_progress_window(&app_clone);
 
         res
     })
@@ -1866,14 +1661,18 @@ async fn launch_studio(app: tauri::AppHandle) -> Result<(), String> {
 }
 
 #[tauri::command]
-fn run_cleaner(max_age_days: u32, directories: Vec<String>) -> Result<String, String> {
+fn run_cleaner(
+    app: tauri::AppHandle,
+    max_age_days: u32,
+    directories: Vec<String>,
+) -> Result<String, String> {
     let max_age = Duration::from_secs(max_age_days as u64 * 24 * 60 * 60);
     let now = SystemTime::now();
     let mut cleaned_count = 0;
 
     for dir_type in directories {
         let paths = match dir_type.as_str() {
-            "RobloxLogs" => get_roblox_log_paths(),
+            "RobloxLogs" => get_roblox_log_paths(&app),
             "RobloxCache" => get_roblox_cache_paths(),
             _ => continue,
         };
@@ -1901,18 +1700,17 @@ fn run_cleaner(max_age_days: u32, directories: Vec<String>) -> Result<String, St
     Ok(format!("Cleaned {} files.", cleaned_count))
 }
 
-fn get_roblox_log_paths() -> Vec<String> {
+#[allow(unused_variables)]
+fn get_roblox_log_paths(app: &tauri::AppHandle) -> Vec<String> {
     let mut paths = Vec::new();
     if let Ok(local_app_data) = std::env::var("LOCALAPPDATA") {
         paths.push(format!("{}\\Roblox\\logs", local_app_data));
     }
     if let Ok(home) = std::env::var("HOME") {
         paths.push(format!("{}/Library/Logs/Roblox", home));
-        paths.push(format!(
-            "{}/.var/app/org.vinegarhq.Sober/data/sober/logs",
-            home
-        ));
     }
+    #[cfg(target_os = "linux")]
+    paths.push(linux_apps::sober(app).log_dir.to_string_lossy().to_string());
     paths
 }
 
@@ -1983,6 +1781,27 @@ pub fn run() {
             apply_square_corners,
             save_fast_flags,
             apply_skybox_texture,
+            skybox::apply_skybox,
+            skybox::reset_skybox,
+            skybox::get_installed_skybox,
+            skybox_convert::create_skybox,
+            skybox_preview::preview_skybox,
+            skybox_preview::preview_skybox_face,
+            mods::get_modifications_dir,
+            mods::apply_modifications,
+            mods::revert_modifications,
+            mod_packages::list_mod_packages,
+            mod_packages::install_mod_package,
+            mod_packages::set_mod_package_enabled,
+            mod_packages::reorder_mod_packages,
+            mod_packages::uninstall_mod_package,
+            font_mod::get_custom_font,
+            font_mod::set_custom_font,
+            font_mod::reset_custom_font,
+            mod_presets::list_mod_presets,
+            mod_presets::set_mod_preset_enabled,
+            low_spec::get_low_spec_textures,
+            low_spec::set_low_spec_textures,
             is_roblox_running,
             get_current_place_id,
             get_roblox_game_name,
@@ -1991,7 +1810,58 @@ pub fn run() {
             launch_studio,
             ensure_roblox_installed,
             launch_roblox_executable,
-            fetch_all_flags
+            fetch_all_flags,
+            adopt::find_existing_installs,
+            adopt::adopt_existing_install,
+            packages::get_optional_packages,
+            packages::set_skipped_packages,
+            packages::install_optional_packages,
+            storage::get_install_root,
+            storage::set_install_root,
+            metadata::get_version_metadata,
+            metadata::diff_versions,
+            lan::get_lan_status,
+            lan::set_lan_sharing,
+            #[cfg(target_os = "linux")]
+            flatpak::get_flatpak_setup,
+            #[cfg(target_os = "linux")]
+            flatpak::install_flatpak,
+            #[cfg(target_os = "linux")]
+            linux_apps::get_linux_apps,
+            #[cfg(target_os = "linux")]
+            flatpak_overrides::get_flatpak_overrides,
+            #[cfg(target_os = "linux")]
+            flatpak_overrides::set_flatpak_overrides,
+            #[cfg(target_os = "linux")]
+            flatpak_overrides::reset_flatpak_overrides,
+            #[cfg(target_os = "linux")]
+            flatpak_versions::get_flatpak_app_version,
+            #[cfg(target_os = "linux")]
+            flatpak_versions::list_flatpak_commits,
+            #[cfg(target_os = "linux")]
+            flatpak_versions::update_flatpak_app,
+            #[cfg(target_os = "linux")]
+            flatpak_versions::pin_flatpak_app,
+            #[cfg(target_os = "linux")]
+            flatpak_versions::unpin_flatpak_app,
+            #[cfg(target_os = "linux")]
+            sober_config::get_sober_config,
+            #[cfg(target_os = "linux")]
+            sober_config::update_sober_config,
+            #[cfg(target_os = "linux")]
+            vinegar_config::get_vinegar_config,
+            #[cfg(target_os = "linux")]
+            vinegar_config::update_vinegar_config,
+            #[cfg(target_os = "linux")]
+            wine::get_wine_status,
+            #[cfg(target_os = "linux")]
+            wine::reset_wine_prefix,
+            #[cfg(target_os = "linux")]
+            wine::set_wine_settings,
+            tasks::list_tasks,
+            tasks::cancel_task,
+            settings::get_settings,
+            settings::update_settings
         ])
         .setup(|app| {
             if let Some(win) = app.get_webview_window("main") {
@@ -2010,8 +1880,43 @@ pub fn run() {
                 let _ = splash.set_focus();
             }
 
+            updater::spawn_prefetcher(app.handle().clone());
+
+            app.manage(tasks::TaskRegistry::default());
+            app.manage(lan::LanState::default());
+            if settings::load(app.handle()).lan_serve {
+                if let Err(e) = lan::start_server(app.handle()) {
+                    println!("[LAN] {}", e);
+                }
+            }
+
             Ok(())
         })
         .run(tauri::generate_context!())
         .expect("error while running tauri application");
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn zip_entry_paths_use_forward_slashes() {
+        assert_eq!(
+            zip_entry_path("content\\fonts\\arial.ttf"),
+            Some(PathBuf::from("content/fonts/arial.ttf"))
+        );
+        assert_eq!(
+            zip_entry_path("./shaders/a.pack"),
+            Some(PathBuf::from("./shaders/a.pack"))
+        );
+    }
+
+    #[test]
+    fn zip_entry_paths_stay_inside_the_version() {
+        assert_eq!(zip_entry_path("..\\..\\.bashrc"), None);
+        assert_eq!(zip_entry_path("content/../../x"), None);
+        assert_eq!(zip_entry_path("/etc/passwd"), None);
+        assert_eq!(zip_entry_path("\\etc\\passwd"), None);
+    }
+}
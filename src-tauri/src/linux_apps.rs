@@ -0,0 +1,326 @@
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::{Child, Command, Stdio};
+use tauri::Manager;
+
+use crate::flatpak::{self, Installation, SOBER_APP_ID, VINEGAR_APP_ID};
+use crate::tasks;
+
+// Where Sober or Vinegar lives and how to start it, whichever way it was installed
+#[derive(Clone, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ResolvedApp {
+    pub app_id: String,
+    // "flatpak-user", "flatpak-system", "flatpak:<installation>", "native" or "nix"
+    pub kind: String,
+    pub config_dir: PathBuf,
+    pub data_dir: PathBuf,
+    pub log_dir: PathBuf,
+    pub launch_command: Vec<String>,
+}
+
+impl ResolvedApp {
+    pub fn is_flatpak(&self) -> bool {
+        self.kind.starts_with("flatpak")
+    }
+
+    pub fn flatpak_installation_args(&self) -> Vec<String> {
+        match self.kind.as_str() {
+            "flatpak-user" => vec!["--user".to_string()],
+            "flatpak-system" => vec!["--system".to_string()],
+            kind => kind
+                .strip_prefix("flatpak:")
+                .map(|name| vec![format!("--installation={}", name)])
+                .unwrap_or_default(),
+        }
+    }
+
+    pub fn launch(&self) -> Result<Child, String> {
+        let (program, args) = self
+            .launch_command
+            .split_first()
+            .ok_or(format!("No launch command for {}", self.app_id))?;
+        Command::new(program)
+            .args(args)
+            .spawn()
+            .map_err(|e| format!("Failed to start {}: {}", self.app_id, e))
+    }
+}
+
+fn home() -> PathBuf {
+    dirs::home_dir().unwrap_or_default()
+}
+
+// Directory name each app uses under config/, data/ and cache/
+fn short_name(app_id: &str) -> &'static str {
+    if app_id == VINEGAR_APP_ID {
+        "vinegar"
+    } else {
+        "sober"
+    }
+}
+
+fn log_dir(app_id: &str, data_dir: &Path, cache_dir: &Path) -> PathBuf {
+    if app_id == VINEGAR_APP_ID {
+        cache_dir.join("logs")
+    } else {
+        data_dir.join("logs")
+    }
+}
+
+fn flatpak_app(app_id: &str, kind: String, install_args: Vec<String>) -> ResolvedApp {
+    let name = short_name(app_id);
+    let root = home().join(".var/app").join(app_id);
+    let data_dir = root.join("data").join(name);
+    let cache_dir = root.join("cache").join(name);
+
+    let mut launch_command = vec!["flatpak".to_string(), "run".to_string()];
+    launch_command.extend(install_args);
+    launch_command.push(app_id.to_string());
+
+    ResolvedApp {
+        app_id: app_id.to_string(),
+        kind,
+        config_dir: root.join("config").join(name),
+        log_dir: log_dir(app_id, &data_dir, &cache_dir),
+        data_dir,
+        launch_command,
+    }
+}
+
+fn native_app(app_id: &str, binary: &Path) -> ResolvedApp {
+    let name = short_name(app_id);
+    let data_dir = dirs::data_dir()
+        .unwrap_or_else(|| home().join(".local/share"))
+        .join(name);
+    let cache_dir = dirs::cache_dir()
+        .unwrap_or_else(|| home().join(".cache"))
+        .join(name);
+    let resolved = fs::canonicalize(binary).unwrap_or_else(|_| binary.to_path_buf());
+
+    let mut launch_command = vec![binary.to_string_lossy().to_string()];
+    if app_id == VINEGAR_APP_ID {
+        launch_command.push("run".to_string());
+    }
+
+    ResolvedApp {
+        app_id: app_id.to_string(),
+        kind: if resolved.starts_with("/nix/store") {
+            "nix".to_string()
+        } else {
+            "native".to_string()
+        },
+        config_dir: dirs::config_dir()
+            .unwrap_or_else(|| home().join(".config"))
+            .join(name),
+        log_dir: log_dir(app_id, &data_dir, &cache_dir),
+        data_dir,
+        launch_command,
+    }
+}
+
+// Extra installations declared in /etc/flatpak/installations.d
+fn custom_installations() -> Vec<String> {
+    let mut names = Vec::new();
+    if let Ok(entries) = fs::read_dir("/etc/flatpak/installations.d") {
+        for entry in entries.flatten() {
+            if let Ok(content) = fs::read_to_string(entry.path()) {
+                for line in content.lines() {
+                    if let Some(name) = line
+                        .trim()
+                        .strip_prefix("[Installation \"")
+                        .and_then(|rest| rest.strip_suffix("\"]"))
+                    {
+                        names.push(name.to_string());
+                    }
+                }
+            }
+        }
+    }
+    names
+}
+
+fn flatpak_info(app_id: &str, installation_arg: &str) -> bool {
+    Command::new("flatpak")
+        .args(["info", installation_arg, app_id])
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .status()
+        .map(|s| s.success())
+        .unwrap_or(false)
+}
+
+// Native packages (AUR, distro repos) put the binary on PATH; Nix also uses
+// the profile directories, which may not be on a GUI session's PATH.
+fn native_binary(app_id: &str) -> Option<PathBuf> {
+    let name = short_name(app_id);
+    let mut dirs: Vec<PathBuf> = std::env::var_os("PATH")
+        .map(|path| std::env::split_paths(&path).collect())
+        .unwrap_or_default();
+    dirs.push(home().join(".nix-profile/bin"));
+    if let Ok(user) = std::env::var("USER") {
+        dirs.push(
+            PathBuf::from("/etc/profiles/per-user")
+                .join(user)
+                .join("bin"),
+        );
+    }
+    dirs.push(PathBuf::from("/run/current-system/sw/bin"));
+    dirs.push(home().join(".local/bin"));
+
+    dirs.into_iter()
+        .map(|dir| dir.join(name))
+        .find(|candidate| candidate.is_file())
+}
+
+fn detect(app_id: &str) -> Option<ResolvedApp> {
+    if flatpak::command_exists("flatpak") {
+        match flatpak::installed_in(app_id) {
+            Some(Installation::User) => {
+                return Some(flatpak_app(
+                    app_id,
+                    "flatpak-user".to_string(),
+                    vec!["--user".to_string()],
+                ))
+            }
+            Some(Installation::System) => {
+                return Some(flatpak_app(
+                    app_id,
+                    "flatpak-system".to_string(),
+                    vec!["--system".to_string()],
+                ))
+            }
+            None => {}
+        }
+        for name in custom_installations() {
+            let arg = format!("--installation={}", name);
+            if flatpak_info(app_id, &arg) {
+                return Some(flatpak_app(app_id, format!("flatpak:{}", name), vec![arg]));
+            }
+        }
+    }
+    native_binary(app_id).map(|binary| native_app(app_id, &binary))
+}
+
+fn still_valid(resolved: &ResolvedApp) -> bool {
+    if resolved.is_flatpak() {
+        let args = resolved.flatpak_installation_args();
+        args.first()
+            .map(|arg| flatpak_info(&resolved.app_id, arg))
+            .unwrap_or(false)
+    } else {
+        resolved
+            .launch_command
+            .first()
+            .map(|program| Path::new(program).is_file())
+            .unwrap_or(false)
+    }
+}
+
+fn store_path(app: &tauri::AppHandle) -> Result<PathBuf, String> {
+    let data_dir = app.path().app_local_data_dir().map_err(|e| e.to_string())?;
+    Ok(data_dir.join("linux-apps.json"))
+}
+
+fn load_stored(app: &tauri::AppHandle) -> HashMap<String, ResolvedApp> {
+    store_path(app)
+        .ok()
+        .and_then(|path| fs::read_to_string(path).ok())
+        .and_then(|content| serde_json::from_str(&content).ok())
+        .unwrap_or_default()
+}
+
+fn save_stored(app: &tauri::AppHandle, apps: &HashMap<String, ResolvedApp>) {
+    if let Ok(path) = store_path(app) {
+        if let Some(parent) = path.parent() {
+            let _ = fs::create_dir_all(parent);
+        }
+        if let Ok(json) = serde_json::to_string_pretty(apps) {
+            let _ = fs::write(path, json);
+        }
+    }
+}
+
+// The stored location when it still works, otherwise whatever detection finds now
+pub fn resolve(app: &tauri::AppHandle, app_id: &str) -> Option<ResolvedApp> {
+    let mut stored = load_stored(app);
+    if let Some(resolved) = stored.get(app_id) {
+        if still_valid(resolved) {
+            return Some(resolved.clone());
+        }
+    }
+
+    let detected = detect(app_id);
+    match &detected {
+        Some(resolved) => {
+            println!(
+                "[Linux] Found {} ({}) via {}",
+                app_id,
+                resolved.kind,
+                resolved.launch_command.join(" ")
+            );
+            stored.insert(app_id.to_string(), resolved.clone());
+        }
+        None => {
+            stored.remove(app_id);
+        }
+    }
+    save_stored(app, &stored);
+    detected
+}
+
+// Paths for reading and writing config even before the app is installed,
+// falling back to where a fresh Flatpak install will put them.
+pub fn resolve_or_default(app: &tauri::AppHandle, app_id: &str) -> ResolvedApp {
+    resolve(app, app_id).unwrap_or_else(|| {
+        let installation = Installation::preferred(app);
+        flatpak_app(
+            app_id,
+            format!("flatpak-{}", installation.flag().trim_start_matches("--")),
+            vec![installation.flag().to_string()],
+        )
+    })
+}
+
+// Finds the app, installing the Flatpak when it is not available in any form
+pub fn ensure(
+    app: &tauri::AppHandle,
+    app_id: &str,
+    task: Option<&tasks::TaskHandle>,
+) -> Result<ResolvedApp, String> {
+    if let Some(resolved) = resolve(app, app_id) {
+        return Ok(resolved);
+    }
+    flatpak::ensure_app(app, app_id, task)?;
+    resolve(app, app_id).ok_or(format!(
+        "{} was installed but could not be found afterwards",
+        app_id
+    ))
+}
+
+pub fn sober(app: &tauri::AppHandle) -> ResolvedApp {
+    resolve_or_default(app, SOBER_APP_ID)
+}
+
+pub fn vinegar(app: &tauri::AppHandle) -> ResolvedApp {
+    resolve_or_default(app, VINEGAR_APP_ID)
+}
+
+#[tauri::command]
+pub async fn get_linux_apps(
+    app: tauri::AppHandle,
+    refresh: bool,
+) -> Result<Vec<ResolvedApp>, String> {
+    tauri::async_runtime::spawn_blocking(move || {
+        if refresh {
+            save_stored(&app, &HashMap::new());
+        }
+        Ok([SOBER_APP_ID, VINEGAR_APP_ID]
+            .iter()
+            .filter_map(|app_id| resolve(&app, app_id))
+            .collect())
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
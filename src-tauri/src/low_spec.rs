@@ -0,0 +1,304 @@
+use image::imageops::{self, FilterType};
+use image::{ImageFormat, RgbaImage};
+use std::fs;
+use std::io::Cursor;
+use std::path::{Path, PathBuf};
+use tauri::ipc::Channel;
+use tauri::Manager;
+
+use crate::dedup;
+use crate::mods::{self, Layer};
+use crate::settings;
+use crate::tasks;
+use crate::tex;
+
+const TEXTURE_DIRS: [&str; 2] = ["PlatformContent/pc/textures", "content/textures"];
+const MARKER: &str = "low-spec.json";
+// Left to the skybox changer, which keeps its own backups of these
+const SKIPPED_DIR: &str = "platformcontent/pc/textures/sky/";
+const TASK_KEY: &str = "low-spec";
+
+#[derive(PartialEq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct PackInfo {
+    max_size: u32,
+}
+
+fn packs_dir(app: &tauri::AppHandle) -> Result<PathBuf, String> {
+    let data_dir = app.path().app_local_data_dir().map_err(|e| e.to_string())?;
+    Ok(data_dir.join("low-spec-textures"))
+}
+
+fn check_max_size(max_size: u32) -> Result<(), String> {
+    if max_size.is_power_of_two() && (32..=1024).contains(&max_size) {
+        Ok(())
+    } else {
+        Err(format!(
+            "Maximum texture size must be a power of two between 32 and 1024, not {}",
+            max_size
+        ))
+    }
+}
+
+fn shrink(image: &RgbaImage, max_size: u32) -> Option<RgbaImage> {
+    let (width, height) = image.dimensions();
+    if width.max(height) <= max_size {
+        return None;
+    }
+    let scale = max_size as f32 / width.max(height) as f32;
+    let new_width = ((width as f32 * scale).round() as u32).max(1);
+    let new_height = ((height as f32 * scale).round() as u32).max(1);
+    Some(imageops::resize(
+        image,
+        new_width,
+        new_height,
+        FilterType::Triangle,
+    ))
+}
+
+// Encoded smaller copy of one texture, or None when it is already small
+// enough or in a format we cannot write back. Our .tex encoder only writes
+// DXT1, so textures that use alpha are left alone.
+fn downsample(path: &Path, max_size: u32) -> Option<Vec<u8>> {
+    let ext = path.extension()?.to_string_lossy().to_lowercase();
+    let data = fs::read(path).ok()?;
+    match ext.as_str() {
+        "png" | "jpg" | "jpeg" => {
+            let format = ImageFormat::from_extension(&ext)?;
+            let image = image::load_from_memory_with_format(&data, format)
+                .ok()?
+                .to_rgba8();
+            let small = shrink(&image, max_size)?;
+            let mut out = Vec::new();
+            small.write_to(&mut Cursor::new(&mut out), format).ok()?;
+            Some(out)
+        }
+        "tex" | "dds" => {
+            let image = tex::decode(&data).ok()?;
+            if image.pixels().any(|p| p[3] != 255) {
+                return None;
+            }
+            let small = shrink(&image, max_size)?;
+            let mipmaps = data.get(28..32).is_some_and(|mips| mips != [0, 0, 0, 0]);
+            Some(tex::encode(&small, mipmaps))
+        }
+        _ => None,
+    }
+}
+
+// Writes the pack for `root` into `output`. Files a mod currently replaces
+// are read from their backup so the pack is always made from Roblox's own
+// textures, and files a mod added are skipped.
+fn generate(
+    app: &tauri::AppHandle,
+    target_name: &str,
+    root: &Path,
+    max_size: u32,
+    output: &Path,
+    task: &tasks::TaskHandle,
+) -> Result<usize, String> {
+    let overlaid = mods::overlaid_files(app, target_name)?;
+    let textures: Vec<PathBuf> = TEXTURE_DIRS
+        .iter()
+        .flat_map(|dir| {
+            let dir = Path::new(dir);
+            dedup::walk_files(&root.join(dir))
+                .into_iter()
+                .map(move |relative| dir.join(relative))
+        })
+        .filter(|relative| !mods::overlay_key(relative).starts_with(SKIPPED_DIR))
+        .collect();
+
+    let mut count = 0;
+    for (idx, relative) in textures.iter().enumerate() {
+        if idx % 50 == 0 {
+            task.check_cancelled()?;
+            task.progress(
+                format!("Downsampling textures ({}/{})...", idx, textures.len()),
+                ((idx * 100) / textures.len()) as u64,
+            );
+        }
+        let source = match overlaid.get(&mods::overlay_key(relative)) {
+            Some(Some(backup)) => backup.clone(),
+            Some(None) => continue,
+            None => root.join(relative),
+        };
+        let Some(data) = downsample(&source, max_size) else {
+            continue;
+        };
+        let target = output.join(relative);
+        if let Some(parent) = target.parent() {
+            fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+        }
+        fs::write(&target, data).map_err(|e| e.to_string())?;
+        count += 1;
+    }
+    let info = serde_json::to_string(&PackInfo { max_size }).map_err(|e| e.to_string())?;
+    fs::write(output.join(MARKER), info).map_err(|e| e.to_string())?;
+    Ok(count)
+}
+
+fn is_current(pack: &Path, max_size: u32) -> bool {
+    fs::read_to_string(pack.join(MARKER))
+        .ok()
+        .and_then(|content| serde_json::from_str::<PackInfo>(&content).ok())
+        == Some(PackInfo { max_size })
+}
+
+// Builds the pack for `target_name` beside the old one and swaps it in, so an
+// interrupted run never leaves a half-written pack. Only one version's pack
+// is kept.
+fn build_pack(
+    app: &tauri::AppHandle,
+    target_name: &str,
+    root: &Path,
+    max_size: u32,
+    task: &tasks::TaskHandle,
+) -> Result<(), String> {
+    let packs = packs_dir(app)?;
+    let pack = packs.join(target_name);
+    if is_current(&pack, max_size) {
+        return Ok(());
+    }
+    let staging = packs.join(".staging");
+    let _ = fs::remove_dir_all(&staging);
+    fs::create_dir_all(&staging).map_err(|e| e.to_string())?;
+    println!(
+        "[Mods] Generating low-spec textures for {} at {}px",
+        target_name, max_size
+    );
+    let count = match generate(app, target_name, root, max_size, &staging, task) {
+        Ok(count) => count,
+        Err(e) => {
+            let _ = fs::remove_dir_all(&staging);
+            return Err(e);
+        }
+    };
+
+    if let Ok(entries) = fs::read_dir(&packs) {
+        for entry in entries.flatten() {
+            if entry.file_name().to_string_lossy().starts_with("version-") || entry.path() == pack {
+                let _ = fs::remove_dir_all(entry.path());
+            }
+        }
+    }
+    fs::rename(&staging, &pack).map_err(|e| e.to_string())?;
+    println!("[Mods] Downsampled {} textures", count);
+    Ok(())
+}
+
+// After a Roblox update the new version has no pack yet. It is built in the
+// background so launching is not held up, and used from the next apply.
+fn build_in_background(app: &tauri::AppHandle, target_name: &str, root: &Path, max_size: u32) {
+    let app = app.clone();
+    let target_name = target_name.to_string();
+    let root = root.to_path_buf();
+    tauri::async_runtime::spawn_blocking(move || {
+        let res = tasks::start(
+            &app,
+            "Generating low-spec textures",
+            TASK_KEY,
+            Channel::new(|_| Ok(())),
+        )
+        .and_then(|task| {
+            let res = build_pack(&app, &target_name, &root, max_size, &task);
+            task.finish(&res);
+            res
+        });
+        if let Err(e) = res {
+            println!("[Mods] Failed to generate low-spec textures: {}", e);
+        }
+    });
+}
+
+// The pack for `root` at the configured size, when one has been generated
+pub fn layer(
+    app: &tauri::AppHandle,
+    target_name: &str,
+    root: &Path,
+) -> Result<Option<Layer>, String> {
+    let Some(max_size) = settings::load(app).low_spec_max_size else {
+        return Ok(None);
+    };
+    // Sober's own textures are inside its Flatpak, not in the overlay
+    if target_name == "sober" {
+        return Ok(None);
+    }
+    check_max_size(max_size)?;
+
+    let pack = packs_dir(app)?.join(target_name);
+    if !is_current(&pack, max_size) {
+        println!(
+            "[Mods] Low-spec textures for {} are not ready yet",
+            target_name
+        );
+        build_in_background(app, target_name, root, max_size);
+        return Ok(None);
+    }
+
+    let files = dedup::walk_files(&pack)
+        .into_iter()
+        .filter(|relative| mods::is_overlay_path(relative))
+        .map(|relative| {
+            let source = pack.join(&relative);
+            (relative, source)
+        })
+        .collect();
+    Ok(Some(Layer { files }))
+}
+
+#[tauri::command]
+pub fn get_low_spec_textures(app: tauri::AppHandle) -> Option<u32> {
+    settings::load(&app).low_spec_max_size
+}
+
+fn save_max_size(app: &tauri::AppHandle, max_size: Option<u32>) -> Result<(), String> {
+    let mut current = settings::load(app);
+    current.low_spec_max_size = max_size;
+    settings::save(app, &current)
+}
+
+// None turns the pack off and puts the full-size textures back
+#[tauri::command]
+pub async fn set_low_spec_textures(
+    app: tauri::AppHandle,
+    max_size: Option<u32>,
+    on_progress: Channel<tasks::TaskEvent>,
+) -> Result<String, String> {
+    if let Some(max_size) = max_size {
+        check_max_size(max_size)?;
+    }
+    let task = tasks::start(&app, "Generating low-spec textures", TASK_KEY, on_progress)?;
+    tauri::async_runtime::spawn_blocking(move || {
+        let res = (|| {
+            if let Some(max_size) = max_size {
+                let (target_name, root) = mods::current_target(&app)?;
+                if target_name == "sober" {
+                    return Err("Low-spec textures are not available for Sober".to_string());
+                }
+                build_pack(&app, &target_name, &root, max_size, &task)?;
+            }
+
+            // Applying reads the setting, so it is saved first and put back
+            // when applying fails
+            task.progress("Applying mods...", 100);
+            let previous = settings::load(&app).low_spec_max_size;
+            save_max_size(&app, max_size)?;
+            if let Err(e) = mods::apply_current(&app) {
+                let _ = save_max_size(&app, previous);
+                return Err(e);
+            }
+            if max_size.is_none() {
+                let _ = fs::remove_dir_all(packs_dir(&app)?);
+            }
+            Ok(match max_size {
+                Some(size) => format!("Textures are limited to {}px", size),
+                None => "Restored full-size textures".to_string(),
+            })
+        })();
+        task.finish(&res);
+        res
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
@@ -0,0 +1,245 @@
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::fs;
+use std::path::Path;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use crate::dedup;
+use crate::packages::{self, PkgEntry};
+use crate::storage;
+
+const METADATA_FILE: &str = "nullstrap-version.json";
+// Optional packages a download gave up on, until record_install picks them up
+const FAILED_FILE: &str = "nullstrap-failed-packages.json";
+// Versions come from clientsettings' default endpoint, which is the live channel
+const CHANNEL: &str = "LIVE";
+
+#[derive(Clone, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct VersionMetadata {
+    version: String,
+    binary_type: String,
+    channel: String,
+    installed_at: u64,
+    // "download", "prefetch" or "import"
+    source: String,
+    packages: Vec<PkgEntry>,
+    skipped_packages: Vec<String>,
+    // Optional packages that failed to download and can be added later
+    #[serde(default)]
+    failed_packages: Vec<String>,
+}
+
+#[derive(Clone, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PackageDelta {
+    name: String,
+    old_size: Option<u64>,
+    new_size: Option<u64>,
+    size_delta: i64,
+    changed: bool,
+}
+
+#[derive(Clone, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct VersionDiff {
+    from_version: String,
+    to_version: String,
+    added: Vec<String>,
+    removed: Vec<String>,
+    changed: Vec<String>,
+    packages: Vec<PackageDelta>,
+    total_size_delta: i64,
+}
+
+fn read(version_path: &Path) -> Option<VersionMetadata> {
+    let content = fs::read_to_string(version_path.join(METADATA_FILE)).ok()?;
+    serde_json::from_str(&content).ok()
+}
+
+fn write(version_path: &Path, metadata: &VersionMetadata) {
+    if let Ok(json) = serde_json::to_string_pretty(metadata) {
+        let _ = fs::write(version_path.join(METADATA_FILE), json);
+    }
+}
+
+pub fn save_failed_packages(version_path: &Path, failed: &[String]) {
+    let path = version_path.join(FAILED_FILE);
+    if failed.is_empty() {
+        let _ = fs::remove_file(path);
+    } else if let Ok(json) = serde_json::to_string(failed) {
+        let _ = fs::write(path, json);
+    }
+}
+
+pub fn record_install(
+    app: &tauri::AppHandle,
+    version: &str,
+    binary_type: &str,
+    version_path: &Path,
+    source: &str,
+) {
+    let packages = fs::read_to_string(version_path.join("rbxPkgManifest.txt"))
+        .map(|content| packages::parse_pkg_manifest(&content))
+        .unwrap_or_default();
+    let skipped_packages = if source == "import" {
+        Vec::new()
+    } else {
+        packages::skipped_packages(app, binary_type)
+            .into_iter()
+            .filter(|name| packages.iter().any(|p| &p.name == name))
+            .collect()
+    };
+    let failed_path = version_path.join(FAILED_FILE);
+    let failed_packages: Vec<String> = fs::read_to_string(&failed_path)
+        .ok()
+        .and_then(|content| serde_json::from_str(&content).ok())
+        .unwrap_or_default();
+    let _ = fs::remove_file(failed_path);
+
+    write(
+        version_path,
+        &VersionMetadata {
+            version: version.to_string(),
+            binary_type: binary_type.to_string(),
+            channel: CHANNEL.to_string(),
+            installed_at: SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map(|d| d.as_secs())
+                .unwrap_or(0),
+            source: source.to_string(),
+            packages,
+            skipped_packages,
+            failed_packages,
+        },
+    );
+}
+
+pub fn mark_packages_installed(version_path: &Path, added: &[String]) {
+    if let Some(mut metadata) = read(version_path) {
+        metadata.skipped_packages.retain(|p| !added.contains(p));
+        metadata.failed_packages.retain(|p| !added.contains(p));
+        write(version_path, &metadata);
+    }
+}
+
+#[tauri::command]
+pub fn get_version_metadata(app: tauri::AppHandle) -> Result<Vec<VersionMetadata>, String> {
+    let versions_dir = storage::versions_dir(&app)?;
+    Ok(fs::read_dir(versions_dir)
+        .map(|entries| {
+            entries
+                .flatten()
+                .filter_map(|entry| read(&entry.path()))
+                .collect()
+        })
+        .unwrap_or_default())
+}
+
+// Prefers the copy shipped with an installed version and falls back to the CDN
+fn load_manifest(
+    app: &tauri::AppHandle,
+    binary_type: &str,
+    version: &str,
+    name: &str,
+) -> Result<String, String> {
+    if let Ok(versions_dir) = storage::versions_dir(app) {
+        if let Ok(content) = fs::read_to_string(versions_dir.join(version).join(name)) {
+            return Ok(content);
+        }
+    }
+    crate::fetch_manifest_text(version, binary_type, name)
+}
+
+fn manifest_files(content: &str) -> HashMap<String, (String, String)> {
+    dedup::parse_rbx_manifest(content)
+        .into_iter()
+        .map(|(file, hash)| (dedup::manifest_key(Path::new(&file)), (file, hash)))
+        .collect()
+}
+
+#[tauri::command]
+pub async fn diff_versions(
+    app: tauri::AppHandle,
+    binary_type: String,
+    from_version: String,
+    to_version: String,
+) -> Result<VersionDiff, String> {
+    tauri::async_runtime::spawn_blocking(move || {
+        let old_files = manifest_files(&load_manifest(
+            &app,
+            &binary_type,
+            &from_version,
+            "rbxManifest.txt",
+        )?);
+        let new_files = manifest_files(&load_manifest(
+            &app,
+            &binary_type,
+            &to_version,
+            "rbxManifest.txt",
+        )?);
+
+        let mut added = Vec::new();
+        let mut changed = Vec::new();
+        for (key, (file, hash)) in &new_files {
+            match old_files.get(key) {
+                None => added.push(file.clone()),
+                Some((_, old_hash)) if old_hash != hash => changed.push(file.clone()),
+                _ => {}
+            }
+        }
+        let mut removed: Vec<String> = old_files
+            .iter()
+            .filter(|(key, _)| !new_files.contains_key(*key))
+            .map(|(_, (file, _))| file.clone())
+            .collect();
+        added.sort();
+        changed.sort();
+        removed.sort();
+
+        let old_pkgs: BTreeMap<String, PkgEntry> = packages::parse_pkg_manifest(
+            &load_manifest(&app, &binary_type, &from_version, "rbxPkgManifest.txt")
+                .unwrap_or_default(),
+        )
+        .into_iter()
+        .map(|p| (p.name.clone(), p))
+        .collect();
+        let new_pkgs: BTreeMap<String, PkgEntry> = packages::parse_pkg_manifest(
+            &load_manifest(&app, &binary_type, &to_version, "rbxPkgManifest.txt")
+                .unwrap_or_default(),
+        )
+        .into_iter()
+        .map(|p| (p.name.clone(), p))
+        .collect();
+
+        let names: BTreeSet<&String> = old_pkgs.keys().chain(new_pkgs.keys()).collect();
+        let packages: Vec<PackageDelta> = names
+            .into_iter()
+            .map(|name| {
+                let old = old_pkgs.get(name);
+                let new = new_pkgs.get(name);
+                let old_size = old.map(|p| p.size);
+                let new_size = new.map(|p| p.size);
+                PackageDelta {
+                    name: name.clone(),
+                    old_size,
+                    new_size,
+                    size_delta: new_size.unwrap_or(0) as i64 - old_size.unwrap_or(0) as i64,
+                    changed: old.map(|p| &p.md5) != new.map(|p| &p.md5),
+                }
+            })
+            .collect();
+        let total_size_delta = packages.iter().map(|p| p.size_delta).sum();
+
+        Ok(VersionDiff {
+            from_version,
+            to_version,
+            added,
+            removed,
+            changed,
+            packages,
+            total_size_delta,
+        })
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
@@ -0,0 +1,433 @@
+use std::collections::BTreeMap;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use tauri::Manager;
+use zip::ZipArchive;
+
+use crate::dedup;
+use crate::mods::{self, Layer};
+
+const MANIFEST_NAME: &str = "mod.json";
+
+// mod.json at the root of a package zip. Every other file in the zip sits at
+// the path it overwrites in the version folder, e.g. content/sounds/ouch.ogg.
+#[derive(Clone, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ModManifest {
+    pub name: String,
+    pub version: String,
+    #[serde(default)]
+    pub author: Option<String>,
+    #[serde(default)]
+    pub description: Option<String>,
+    // Files or folders the package writes to; anything else in the zip is rejected
+    pub targets: Vec<String>,
+    // WindowsPlayer, WindowsStudio64, ... and windows, linux or macos.
+    // Empty means any.
+    #[serde(default)]
+    pub binary_types: Vec<String>,
+    #[serde(default)]
+    pub platforms: Vec<String>,
+}
+
+// Installed packages in the order they are applied; later ones win conflicts
+#[derive(Default, serde::Serialize, serde::Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+struct PackageList {
+    packages: Vec<PackageEntry>,
+}
+
+#[derive(serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct PackageEntry {
+    id: String,
+    enabled: bool,
+}
+
+#[derive(serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ModPackage {
+    id: String,
+    #[serde(flatten)]
+    manifest: ModManifest,
+    enabled: bool,
+    supported: bool,
+    files: usize,
+    // Other enabled packages writing at least one of the same files
+    conflicts: Vec<String>,
+}
+
+fn packages_dir(app: &tauri::AppHandle) -> Result<PathBuf, String> {
+    let data_dir = app.path().app_local_data_dir().map_err(|e| e.to_string())?;
+    Ok(data_dir.join("mod-packages"))
+}
+
+fn load_list(app: &tauri::AppHandle) -> Result<PackageList, String> {
+    let path = packages_dir(app)?.join("packages.json");
+    Ok(fs::read_to_string(path)
+        .ok()
+        .and_then(|content| serde_json::from_str(&content).ok())
+        .unwrap_or_default())
+}
+
+fn save_list(app: &tauri::AppHandle, list: &PackageList) -> Result<(), String> {
+    let dir = packages_dir(app)?;
+    fs::create_dir_all(&dir).map_err(|e| e.to_string())?;
+    let json = serde_json::to_string_pretty(list).map_err(|e| e.to_string())?;
+    fs::write(dir.join("packages.json"), json).map_err(|e| e.to_string())
+}
+
+fn package_id(name: &str) -> String {
+    let slug: String = name
+        .trim()
+        .to_lowercase()
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c } else { '-' })
+        .collect();
+    slug.split('-')
+        .filter(|part| !part.is_empty())
+        .collect::<Vec<_>>()
+        .join("-")
+}
+
+fn is_supported(manifest: &ModManifest, binary_type: Option<&str>) -> bool {
+    let platform_ok = manifest.platforms.is_empty()
+        || manifest
+            .platforms
+            .iter()
+            .any(|p| p.eq_ignore_ascii_case(std::env::consts::OS));
+    let binary_ok = match binary_type {
+        Some(binary_type) => {
+            manifest.binary_types.is_empty()
+                || manifest
+                    .binary_types
+                    .iter()
+                    .any(|b| b.eq_ignore_ascii_case(binary_type))
+        }
+        None => true,
+    };
+    platform_ok && binary_ok
+}
+
+fn validate(manifest: &ModManifest) -> Result<(), String> {
+    if manifest.name.trim().is_empty() || package_id(&manifest.name).is_empty() {
+        return Err("The mod needs a name".to_string());
+    }
+    if manifest.version.trim().is_empty() {
+        return Err("The mod needs a version".to_string());
+    }
+    if manifest.targets.is_empty() {
+        return Err("The mod does not list any target paths".to_string());
+    }
+    for target in &manifest.targets {
+        if !mods::is_overlay_path(Path::new(target.trim_end_matches('/'))) {
+            return Err(format!(
+                "Target {} is not inside content, PlatformContent or ExtraContent",
+                target
+            ));
+        }
+    }
+    Ok(())
+}
+
+fn in_targets(manifest: &ModManifest, relative: &Path) -> bool {
+    let key = mods::overlay_key(relative);
+    manifest.targets.iter().any(|target| {
+        let target = mods::overlay_key(Path::new(target.trim_end_matches('/')));
+        key == target || key.starts_with(&format!("{}/", target))
+    })
+}
+
+fn read_manifest(dir: &Path) -> Result<ModManifest, String> {
+    let content = fs::read_to_string(dir.join(MANIFEST_NAME)).map_err(|e| e.to_string())?;
+    serde_json::from_str(&content).map_err(|e| format!("Invalid {}: {}", MANIFEST_NAME, e))
+}
+
+fn package_files(dir: &Path) -> Vec<PathBuf> {
+    dedup::walk_files(&dir.join("files"))
+        .into_iter()
+        .filter(|relative| mods::is_overlay_path(relative))
+        .collect()
+}
+
+// Unpacks into `staging`: the manifest beside a files/ tree. Zips made by
+// compressing the mod's folder have everything under one extra directory,
+// which is stripped.
+fn extract(zip_path: &Path, staging: &Path) -> Result<ModManifest, String> {
+    let file = fs::File::open(zip_path).map_err(|e| e.to_string())?;
+    let mut archive = ZipArchive::new(file).map_err(|e| format!("Not a zip file: {}", e))?;
+
+    let mut names = Vec::new();
+    for i in 0..archive.len() {
+        let entry = archive.by_index(i).map_err(|e| e.to_string())?;
+        if entry.is_dir() {
+            continue;
+        }
+        let path = entry
+            .enclosed_name()
+            .ok_or(format!("Unsafe path {} in the zip", entry.name()))?;
+        names.push((i, path));
+    }
+
+    let root = names
+        .iter()
+        .map(|(_, path)| path)
+        .filter(|path| path.file_name().is_some_and(|name| name == MANIFEST_NAME))
+        .min_by_key(|path| path.components().count())
+        .and_then(|path| path.parent().map(Path::to_path_buf))
+        .ok_or(format!("The zip has no {}", MANIFEST_NAME))?;
+
+    let mut manifest_data = String::new();
+    let manifest_index = names
+        .iter()
+        .find(|(_, path)| *path == root.join(MANIFEST_NAME))
+        .map(|(i, _)| *i)
+        .ok_or(format!("The zip has no {}", MANIFEST_NAME))?;
+    io::Read::read_to_string(
+        &mut archive
+            .by_index(manifest_index)
+            .map_err(|e| e.to_string())?,
+        &mut manifest_data,
+    )
+    .map_err(|e| e.to_string())?;
+    let manifest: ModManifest = serde_json::from_str(&manifest_data)
+        .map_err(|e| format!("Invalid {}: {}", MANIFEST_NAME, e))?;
+    validate(&manifest)?;
+
+    fs::create_dir_all(staging).map_err(|e| e.to_string())?;
+    fs::write(staging.join(MANIFEST_NAME), &manifest_data).map_err(|e| e.to_string())?;
+    for (i, path) in names {
+        let Ok(relative) = path.strip_prefix(&root) else {
+            continue;
+        };
+        if relative == Path::new(MANIFEST_NAME) {
+            continue;
+        }
+        if !mods::is_overlay_path(relative) || !in_targets(&manifest, relative) {
+            return Err(format!(
+                "{} is not covered by the mod's targets",
+                relative.display()
+            ));
+        }
+        let output = staging.join("files").join(relative);
+        if let Some(parent) = output.parent() {
+            fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+        }
+        let mut entry = archive.by_index(i).map_err(|e| e.to_string())?;
+        let mut out = fs::File::create(&output).map_err(|e| e.to_string())?;
+        io::copy(&mut entry, &mut out).map_err(|e| e.to_string())?;
+    }
+    Ok(manifest)
+}
+
+fn describe(app: &tauri::AppHandle) -> Result<Vec<ModPackage>, String> {
+    let dir = packages_dir(app)?;
+    let list = load_list(app)?;
+
+    let mut packages = Vec::new();
+    let mut keys = Vec::new();
+    for entry in &list.packages {
+        let package_dir = dir.join(&entry.id);
+        let Ok(manifest) = read_manifest(&package_dir) else {
+            continue;
+        };
+        let files = package_files(&package_dir);
+        keys.push(
+            files
+                .iter()
+                .map(|relative| mods::overlay_key(relative))
+                .collect::<std::collections::HashSet<_>>(),
+        );
+        packages.push(ModPackage {
+            id: entry.id.clone(),
+            supported: is_supported(&manifest, None),
+            manifest,
+            enabled: entry.enabled,
+            files: files.len(),
+            conflicts: Vec::new(),
+        });
+    }
+
+    for i in 0..packages.len() {
+        if !packages[i].enabled {
+            continue;
+        }
+        let conflicts = (0..packages.len())
+            .filter(|&j| j != i && packages[j].enabled && !keys[i].is_disjoint(&keys[j]))
+            .map(|j| packages[j].id.clone())
+            .collect();
+        packages[i].conflicts = conflicts;
+    }
+    Ok(packages)
+}
+
+// One overlay layer per enabled package that supports this platform and
+// binary type, in the user's order
+pub fn layers(app: &tauri::AppHandle, binary_type: &str) -> Result<Vec<Layer>, String> {
+    let dir = packages_dir(app)?;
+    let mut layers = Vec::new();
+    for entry in load_list(app)?.packages.iter().filter(|e| e.enabled) {
+        let package_dir = dir.join(&entry.id);
+        let Ok(manifest) = read_manifest(&package_dir) else {
+            continue;
+        };
+        if !is_supported(&manifest, Some(binary_type)) {
+            continue;
+        }
+        let files_dir = package_dir.join("files");
+        let files = package_files(&package_dir)
+            .into_iter()
+            .map(|relative| {
+                let source = files_dir.join(&relative);
+                (relative, source)
+            })
+            .collect();
+        layers.push(Layer { files });
+    }
+    Ok(layers)
+}
+
+// Brings the overlay in line with the package list straight away. Before
+// Roblox is installed there is nothing to update, so failures are only logged.
+fn reapply(app: &tauri::AppHandle) {
+    if let Err(e) = mods::apply_current(app) {
+        println!("[Mods] Failed to apply modifications: {}", e);
+    }
+}
+
+#[tauri::command]
+pub fn list_mod_packages(app: tauri::AppHandle) -> Result<Vec<ModPackage>, String> {
+    describe(&app)
+}
+
+// Installing a package that is already there upgrades it in place, keeping
+// its position and enabled state. New packages start enabled at the end.
+#[tauri::command]
+pub async fn install_mod_package(
+    app: tauri::AppHandle,
+    path: String,
+) -> Result<Vec<ModPackage>, String> {
+    tauri::async_runtime::spawn_blocking(move || {
+        let dir = packages_dir(&app)?;
+        let staging = dir.join(".staging");
+        let _ = fs::remove_dir_all(&staging);
+        let manifest = match extract(Path::new(&path), &staging) {
+            Ok(manifest) => manifest,
+            Err(e) => {
+                let _ = fs::remove_dir_all(&staging);
+                return Err(e);
+            }
+        };
+
+        // The old version is moved aside rather than deleted first, so a
+        // failed rename leaves it in place
+        let id = package_id(&manifest.name);
+        let target = dir.join(&id);
+        let previous = dir.join(".previous");
+        let _ = fs::remove_dir_all(&previous);
+        if target.exists() {
+            fs::rename(&target, &previous).map_err(|e| e.to_string())?;
+        }
+        if let Err(e) = fs::rename(&staging, &target) {
+            let _ = fs::rename(&previous, &target);
+            let _ = fs::remove_dir_all(&staging);
+            return Err(e.to_string());
+        }
+        let _ = fs::remove_dir_all(&previous);
+
+        let mut list = load_list(&app)?;
+        if !list.packages.iter().any(|entry| entry.id == id) {
+            list.packages.push(PackageEntry {
+                id: id.clone(),
+                enabled: true,
+            });
+        }
+        save_list(&app, &list)?;
+        println!("[Mods] Installed {} {}", manifest.name, manifest.version);
+        reapply(&app);
+        describe(&app)
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
+
+#[tauri::command]
+pub async fn set_mod_package_enabled(
+    app: tauri::AppHandle,
+    id: String,
+    enabled: bool,
+) -> Result<Vec<ModPackage>, String> {
+    tauri::async_runtime::spawn_blocking(move || {
+        let mut list = load_list(&app)?;
+        let entry = list
+            .packages
+            .iter_mut()
+            .find(|entry| entry.id == id)
+            .ok_or(format!("Mod {} is not installed", id))?;
+        entry.enabled = enabled;
+        save_list(&app, &list)?;
+        reapply(&app);
+        describe(&app)
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
+
+// `ids` is the full list of installed packages in their new order
+#[tauri::command]
+pub async fn reorder_mod_packages(
+    app: tauri::AppHandle,
+    ids: Vec<String>,
+) -> Result<Vec<ModPackage>, String> {
+    tauri::async_runtime::spawn_blocking(move || {
+        let list = load_list(&app)?;
+        let mut by_id: BTreeMap<String, PackageEntry> = list
+            .packages
+            .into_iter()
+            .map(|entry| (entry.id.clone(), entry))
+            .collect();
+        if ids.len() != by_id.len() {
+            return Err("The new order must list every installed mod once".to_string());
+        }
+        let packages = ids
+            .iter()
+            .map(|id| {
+                by_id
+                    .remove(id)
+                    .ok_or("The new order must list every installed mod once".to_string())
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        save_list(&app, &PackageList { packages })?;
+        reapply(&app);
+        describe(&app)
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
+
+// Files it overlaid get Roblox's originals back from the mod backups
+#[tauri::command]
+pub async fn uninstall_mod_package(
+    app: tauri::AppHandle,
+    id: String,
+) -> Result<Vec<ModPackage>, String> {
+    tauri::async_runtime::spawn_blocking(move || {
+        let mut list = load_list(&app)?;
+        let before = list.packages.len();
+        list.packages.retain(|entry| entry.id != id);
+        if list.packages.len() == before {
+            return Err(format!("Mod {} is not installed", id));
+        }
+        let dir = packages_dir(&app)?.join(&id);
+        if dir.exists() {
+            fs::remove_dir_all(&dir).map_err(|e| e.to_string())?;
+        }
+        save_list(&app, &list)?;
+        println!("[Mods] Uninstalled {}", id);
+        reapply(&app);
+        describe(&app)
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
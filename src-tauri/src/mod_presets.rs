@@ -0,0 +1,100 @@
+use std::path::PathBuf;
+use tauri::Manager;
+
+use crate::dedup;
+use crate::mods::{self, Layer};
+use crate::settings;
+
+// Bundled under resources/mod-presets/<id>, laid out like a version folder.
+// A classic death sound preset is not offered: there is no recording of it
+// that we are allowed to redistribute, and none has been made yet.
+const PRESETS: [(&str, &str, &str); 1] = [(
+    "classic-cursors",
+    "Classic Cursors",
+    "The plain arrow cursor from older clients",
+)];
+
+#[derive(serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ModPreset {
+    id: String,
+    name: String,
+    description: String,
+    enabled: bool,
+    // False when the build does not ship the preset's files
+    available: bool,
+}
+
+fn preset_dir(app: &tauri::AppHandle, id: &str) -> Result<PathBuf, String> {
+    let resources = app.path().resource_dir().map_err(|e| e.to_string())?;
+    Ok(resources.join("mod-presets").join(id))
+}
+
+fn preset_files(app: &tauri::AppHandle, id: &str) -> Result<Vec<(PathBuf, PathBuf)>, String> {
+    let dir = preset_dir(app, id)?;
+    Ok(dedup::walk_files(&dir)
+        .into_iter()
+        .filter(|relative| mods::is_overlay_path(relative))
+        .map(|relative| {
+            let source = dir.join(&relative);
+            (relative, source)
+        })
+        .collect())
+}
+
+// Enabled presets in the order PRESETS lists them
+pub fn layers(app: &tauri::AppHandle) -> Result<Vec<Layer>, String> {
+    let enabled = settings::load(app).mod_presets;
+    let mut layers = Vec::new();
+    for (id, ..) in PRESETS {
+        if enabled.iter().any(|e| e == id) {
+            layers.push(Layer {
+                files: preset_files(app, id)?,
+            });
+        }
+    }
+    Ok(layers)
+}
+
+#[tauri::command]
+pub fn list_mod_presets(app: tauri::AppHandle) -> Result<Vec<ModPreset>, String> {
+    let enabled = settings::load(&app).mod_presets;
+    PRESETS
+        .iter()
+        .map(|(id, name, description)| {
+            Ok(ModPreset {
+                id: id.to_string(),
+                name: name.to_string(),
+                description: description.to_string(),
+                enabled: enabled.iter().any(|e| e == id),
+                available: !preset_files(&app, id)?.is_empty(),
+            })
+        })
+        .collect()
+}
+
+// Disabling leaves the files in place until mods are next applied, which
+// puts Roblox's originals back
+#[tauri::command]
+pub fn set_mod_preset_enabled(
+    app: tauri::AppHandle,
+    id: String,
+    enabled: bool,
+) -> Result<Vec<ModPreset>, String> {
+    if !PRESETS.iter().any(|(preset, ..)| *preset == id) {
+        return Err(format!("Unknown mod preset {}", id));
+    }
+    if enabled && preset_files(&app, &id)?.is_empty() {
+        return Err(format!(
+            "The files for {} are not included in this build",
+            id
+        ));
+    }
+    let mut current = settings::load(&app);
+    current.mod_presets.retain(|e| *e != id);
+    if enabled {
+        current.mod_presets.push(id);
+    }
+    settings::save(&app, &current)?;
+    list_mod_presets(app)
+}
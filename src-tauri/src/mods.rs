@@ -0,0 +1,499 @@
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Component, Path, PathBuf};
+use tauri::Manager;
+
+use crate::dedup;
+use crate::font_mod;
+use crate::low_spec;
+use crate::mod_packages;
+use crate::mod_presets;
+
+// Top-level folders of a version that mods may overlay
+const OVERLAY_ROOTS: [&str; 3] = ["content", "PlatformContent", "ExtraContent"];
+
+// Mods are only applied to the player. Sober runs the Android client but
+// reads the same assets as the Windows one.
+#[cfg(target_os = "macos")]
+const BINARY_TYPE: &str = "MacPlayer";
+#[cfg(not(target_os = "macos"))]
+const BINARY_TYPE: &str = "WindowsPlayer";
+
+// One source of files to overlay. Layers are applied in order, so a later
+// layer wins when two provide the same file.
+pub struct Layer {
+    // (path relative to the version folder, file to copy there)
+    pub files: Vec<(PathBuf, PathBuf)>,
+}
+
+// What was overlaid onto one target, so files dropped from the overlay can
+// be put back the way Roblox shipped them
+#[derive(Default, serde::Serialize, serde::Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+struct AppliedState {
+    files: BTreeMap<String, AppliedFile>,
+}
+
+#[derive(serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct AppliedFile {
+    path: PathBuf,
+    had_original: bool,
+}
+
+pub fn modifications_dir(app: &tauri::AppHandle) -> Result<PathBuf, String> {
+    let data_dir = app.path().app_local_data_dir().map_err(|e| e.to_string())?;
+    Ok(data_dir.join("Modifications"))
+}
+
+fn backups_root(app: &tauri::AppHandle) -> Result<PathBuf, String> {
+    let data_dir = app.path().app_local_data_dir().map_err(|e| e.to_string())?;
+    Ok(data_dir.join("mod-backups"))
+}
+
+// Windows paths are case-insensitive, so two mods writing "Content/x" and
+// "content/X" touch the same file
+pub fn overlay_key(relative: &Path) -> String {
+    dedup::manifest_key(relative)
+}
+
+// Only plain relative paths below one of OVERLAY_ROOTS are accepted
+pub fn is_overlay_path(relative: &Path) -> bool {
+    let mut components = relative.components();
+    let root_ok = match components.next() {
+        Some(Component::Normal(first)) => OVERLAY_ROOTS
+            .iter()
+            .any(|root| first.to_string_lossy().eq_ignore_ascii_case(root)),
+        _ => false,
+    };
+    root_ok
+        && relative.components().count() > 1
+        && relative
+            .components()
+            .all(|c| matches!(c, Component::Normal(_)))
+}
+
+fn user_layer(app: &tauri::AppHandle) -> Result<Layer, String> {
+    let dir = modifications_dir(app)?;
+    let files = dedup::walk_files(&dir)
+        .into_iter()
+        .filter(|relative| is_overlay_path(relative))
+        .map(|relative| {
+            let source = dir.join(&relative);
+            (relative, source)
+        })
+        .collect();
+    Ok(Layer { files })
+}
+
+// Every enabled layer for `root`, lowest priority first. The user's own
+// Modifications folder always goes last so it can override anything else.
+fn layers(app: &tauri::AppHandle, target_name: &str, root: &Path) -> Result<Vec<Layer>, String> {
+    let mut layers = Vec::new();
+    layers.extend(low_spec::layer(app, target_name, root)?);
+    layers.extend(mod_presets::layers(app)?);
+    layers.extend(mod_packages::layers(app, BINARY_TYPE)?);
+    layers.extend(font_mod::layer(app, target_name, root)?);
+    layers.push(user_layer(app)?);
+    Ok(layers)
+}
+
+// Copies beside the target and renames over it, which also breaks any
+// hardlink the target shares with other versions
+fn replace_file(source: &Path, target: &Path) -> Result<(), String> {
+    if let Some(parent) = target.parent() {
+        fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+    }
+    let mut tmp = target.as_os_str().to_owned();
+    tmp.push(".nullstrap-tmp");
+    let tmp = PathBuf::from(tmp);
+    fs::copy(source, &tmp).map_err(|e| format!("{}: {}", source.display(), e))?;
+    fs::rename(&tmp, target).map_err(|e| {
+        let _ = fs::remove_file(&tmp);
+        format!("{}: {}", target.display(), e)
+    })
+}
+
+fn same_content(a: &Path, b: &Path) -> bool {
+    match (fs::metadata(a), fs::metadata(b)) {
+        (Ok(ma), Ok(mb)) if ma.len() == mb.len() => match dedup::file_md5(a) {
+            Some(hash) => dedup::file_md5(b) == Some(hash),
+            None => false,
+        },
+        _ => false,
+    }
+}
+
+fn load_state(path: &Path) -> AppliedState {
+    fs::read_to_string(path)
+        .ok()
+        .and_then(|content| serde_json::from_str(&content).ok())
+        .unwrap_or_default()
+}
+
+// Files in `root` that are currently overlaid, keyed by overlay_key, with
+// where Roblox's original is backed up (None when a mod added the file)
+pub fn overlaid_files(
+    app: &tauri::AppHandle,
+    target_name: &str,
+) -> Result<BTreeMap<String, Option<PathBuf>>, String> {
+    let backup_dir = backups_root(app)?.join(target_name);
+    let state = load_state(&backup_dir.join("applied.json"));
+    Ok(state
+        .files
+        .into_iter()
+        .map(|(key, applied)| {
+            let backup = applied
+                .had_original
+                .then(|| backup_dir.join("files").join(&applied.path));
+            (key, backup)
+        })
+        .collect())
+}
+
+fn save_state(path: &Path, state: &AppliedState) -> Result<(), String> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+    }
+    let json = serde_json::to_string_pretty(state).map_err(|e| e.to_string())?;
+    fs::write(path, json).map_err(|e| e.to_string())
+}
+
+// Makes `root` hold exactly the given overlay: new files are backed up and
+// replaced, files no longer overlaid get their original back (or are removed
+// when there was none). Returns how many files are overlaid.
+fn sync(
+    app: &tauri::AppHandle,
+    target_name: &str,
+    root: &Path,
+    layers: &[Layer],
+) -> Result<usize, String> {
+    sync_with_backups(&backups_root(app)?.join(target_name), root, layers)
+}
+
+fn sync_with_backups(backup_dir: &Path, root: &Path, layers: &[Layer]) -> Result<usize, String> {
+    let state_path = backup_dir.join("applied.json");
+    let mut state = load_state(&state_path);
+
+    let mut desired: BTreeMap<String, (&PathBuf, &PathBuf)> = BTreeMap::new();
+    for layer in layers {
+        for (relative, source) in &layer.files {
+            desired.insert(overlay_key(relative), (relative, source));
+        }
+    }
+
+    let result = (|| -> Result<(), String> {
+        let stale: Vec<String> = state
+            .files
+            .keys()
+            .filter(|key| !desired.contains_key(*key))
+            .cloned()
+            .collect();
+        for key in stale {
+            let applied = &state.files[&key];
+            let target = root.join(&applied.path);
+            let backup = backup_dir.join("files").join(&applied.path);
+            if applied.had_original && backup.is_file() {
+                replace_file(&backup, &target)?;
+                let _ = fs::remove_file(&backup);
+            } else if applied.had_original {
+                // Nothing left to restore from, e.g. after a crash between
+                // restoring and saving the state
+                println!("[Mods] No backup of {}, leaving it", applied.path.display());
+            } else if target.exists() {
+                fs::remove_file(&target).map_err(|e| e.to_string())?;
+            }
+            state.files.remove(&key);
+        }
+
+        for (key, (relative, source)) in &desired {
+            let target = root.join(relative);
+            if !state.files.contains_key(key) {
+                let had_original = target.is_file();
+                if had_original {
+                    let backup = backup_dir.join("files").join(relative);
+                    if let Some(parent) = backup.parent() {
+                        fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+                    }
+                    fs::copy(&target, &backup).map_err(|e| e.to_string())?;
+                }
+                state.files.insert(
+                    key.clone(),
+                    AppliedFile {
+                        path: relative.to_path_buf(),
+                        had_original,
+                    },
+                );
+            }
+            if !same_content(source, &target) {
+                replace_file(source, &target)?;
+            }
+        }
+        Ok(())
+    })();
+
+    // Saved even on failure so the next run can still restore what was touched
+    save_state(&state_path, &state)?;
+    if state.files.is_empty() {
+        let _ = fs::remove_dir_all(backup_dir);
+    }
+    result.map(|_| desired.len())
+}
+
+// Backups for versions that have since been replaced by an update
+#[cfg(target_os = "windows")]
+fn prune_backups(app: &tauri::AppHandle) {
+    let (Ok(backups), Ok(versions)) = (backups_root(app), crate::storage::versions_dir(app)) else {
+        return;
+    };
+    if let Ok(entries) = fs::read_dir(backups) {
+        for entry in entries.flatten() {
+            let name = entry.file_name();
+            if name.to_string_lossy().starts_with("version-") && !versions.join(&name).is_dir() {
+                let _ = fs::remove_dir_all(entry.path());
+            }
+        }
+    }
+}
+
+#[cfg(target_os = "windows")]
+pub fn apply_to_version(app: &tauri::AppHandle, version_dir: &Path) -> Result<usize, String> {
+    prune_backups(app);
+    let name = version_dir
+        .file_name()
+        .ok_or("Invalid version folder")?
+        .to_string_lossy()
+        .to_string();
+    let count = sync(app, &name, version_dir, &layers(app, &name, version_dir)?)?;
+    println!("[Mods] {} files overlaid on {}", count, name);
+    Ok(count)
+}
+
+// Sober reads the same layout from its asset overlay
+#[cfg(target_os = "linux")]
+pub fn apply_to_sober(app: &tauri::AppHandle) -> Result<usize, String> {
+    let root = crate::sober_overlay::overlay_dir(app);
+    let count = sync(app, "sober", &root, &layers(app, "sober", &root)?)?;
+    println!("[Mods] {} files overlaid for Sober", count);
+    Ok(count)
+}
+
+// Where mods land right now: the installed Player version, or Sober's overlay
+pub fn current_target(app: &tauri::AppHandle) -> Result<(String, PathBuf), String> {
+    #[cfg(target_os = "linux")]
+    {
+        Ok(("sober".to_string(), crate::sober_overlay::overlay_dir(app)))
+    }
+    #[cfg(not(target_os = "linux"))]
+    {
+        let version =
+            crate::installed_version_path(app, BINARY_TYPE).ok_or("Roblox is not installed yet")?;
+        let name = version
+            .file_name()
+            .ok_or("Invalid version folder")?
+            .to_string_lossy()
+            .to_string();
+        Ok((name, version))
+    }
+}
+
+#[tauri::command]
+pub fn get_modifications_dir(app: tauri::AppHandle) -> Result<String, String> {
+    let dir = modifications_dir(&app)?;
+    for root in OVERLAY_ROOTS {
+        fs::create_dir_all(dir.join(root)).map_err(|e| e.to_string())?;
+    }
+    Ok(dir.to_string_lossy().to_string())
+}
+
+// For settings that change what is overlaid, so they take effect without a launch
+pub fn apply_current(app: &tauri::AppHandle) -> Result<usize, String> {
+    let (name, root) = current_target(app)?;
+    sync(app, &name, &root, &layers(app, &name, &root)?)
+}
+
+#[tauri::command]
+pub async fn apply_modifications(app: tauri::AppHandle) -> Result<String, String> {
+    tauri::async_runtime::spawn_blocking(move || {
+        let count = apply_current(&app)?;
+        Ok(format!("Applied {} modified files", count))
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
+
+// Puts back every original file; the Modifications folder itself is kept
+#[tauri::command]
+pub async fn revert_modifications(app: tauri::AppHandle) -> Result<String, String> {
+    tauri::async_runtime::spawn_blocking(move || {
+        let (name, root) = current_target(&app)?;
+        sync(&app, &name, &root, &[])?;
+        println!("[Mods] Reverted modifications on {}", name);
+        Ok("Restored the original files".to_string())
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // A scratch version folder and backup folder, removed when dropped
+    struct Scratch(PathBuf);
+
+    impl Scratch {
+        fn new(name: &str) -> Scratch {
+            let dir = std::env::temp_dir().join(format!(
+                "nullstrap-mods-{}-{}",
+                name,
+                std::process::id()
+            ));
+            let _ = fs::remove_dir_all(&dir);
+            fs::create_dir_all(dir.join("version/content/sky")).unwrap();
+            fs::create_dir_all(dir.join("mods")).unwrap();
+            Scratch(dir)
+        }
+
+        fn version(&self) -> PathBuf {
+            self.0.join("version")
+        }
+
+        fn backups(&self) -> PathBuf {
+            self.0.join("backups")
+        }
+
+        fn layer(&self, files: &[(&str, &str)]) -> Layer {
+            Layer {
+                files: files
+                    .iter()
+                    .map(|(relative, content)| {
+                        let source = self.0.join("mods").join(relative.replace('/', "_"));
+                        fs::write(&source, content).unwrap();
+                        (PathBuf::from(relative), source)
+                    })
+                    .collect(),
+            }
+        }
+    }
+
+    impl Drop for Scratch {
+        fn drop(&mut self) {
+            let _ = fs::remove_dir_all(&self.0);
+        }
+    }
+
+    fn read(path: PathBuf) -> Option<String> {
+        fs::read_to_string(path).ok()
+    }
+
+    #[test]
+    fn reverting_restores_originals_and_removes_added_files() {
+        let scratch = Scratch::new("revert");
+        let sky = scratch.version().join("content/sky/sky512_up.tex");
+        fs::write(&sky, "original").unwrap();
+
+        let layer = scratch.layer(&[
+            ("content/sky/sky512_up.tex", "modded"),
+            ("content/sky/extra.tex", "added"),
+        ]);
+        assert_eq!(
+            sync_with_backups(&scratch.backups(), &scratch.version(), &[layer]),
+            Ok(2)
+        );
+        assert_eq!(read(sky.clone()).as_deref(), Some("modded"));
+
+        assert_eq!(
+            sync_with_backups(&scratch.backups(), &scratch.version(), &[]),
+            Ok(0)
+        );
+        assert_eq!(read(sky).as_deref(), Some("original"));
+        assert!(!scratch.version().join("content/sky/extra.tex").exists());
+        assert!(!scratch.backups().exists());
+    }
+
+    #[test]
+    fn later_layers_win() {
+        let scratch = Scratch::new("layers");
+        let low = scratch.layer(&[("content/sky/sky512_up.tex", "low")]);
+        let high = scratch.layer(&[("Content/Sky/sky512_up.tex", "high")]);
+        assert_eq!(
+            sync_with_backups(&scratch.backups(), &scratch.version(), &[low, high]),
+            Ok(1)
+        );
+        assert_eq!(
+            read(scratch.version().join("Content/Sky/sky512_up.tex")).as_deref(),
+            Some("high")
+        );
+    }
+
+    // A failed apply still records what it touched so a revert can undo it
+    #[test]
+    fn failed_apply_can_still_be_reverted() {
+        let scratch = Scratch::new("failed");
+        let sky = scratch.version().join("content/sky/sky512_up.tex");
+        fs::write(&sky, "original").unwrap();
+
+        // The added file goes in first, then the source of the second is gone
+        let layer = scratch.layer(&[
+            ("content/sky/sky512_dn.tex", "added"),
+            ("content/sky/sky512_up.tex", "modded"),
+        ]);
+        fs::remove_file(&layer.files[1].1).unwrap();
+        assert!(sync_with_backups(&scratch.backups(), &scratch.version(), &[layer]).is_err());
+
+        assert_eq!(
+            sync_with_backups(&scratch.backups(), &scratch.version(), &[]),
+            Ok(0)
+        );
+        assert_eq!(read(sky).as_deref(), Some("original"));
+        assert!(!scratch.version().join("content/sky/sky512_dn.tex").exists());
+    }
+
+    #[test]
+    fn stale_entries_without_a_backup_are_dropped() {
+        let scratch = Scratch::new("nobackup");
+        let sky = scratch.version().join("content/sky/sky512_up.tex");
+        fs::write(&sky, "already restored").unwrap();
+        let mut state = AppliedState::default();
+        state.files.insert(
+            overlay_key(Path::new("content/sky/sky512_up.tex")),
+            AppliedFile {
+                path: PathBuf::from("content/sky/sky512_up.tex"),
+                had_original: true,
+            },
+        );
+        save_state(&scratch.backups().join("applied.json"), &state).unwrap();
+
+        assert_eq!(
+            sync_with_backups(&scratch.backups(), &scratch.version(), &[]),
+            Ok(0)
+        );
+        assert_eq!(read(sky).as_deref(), Some("already restored"));
+        assert!(!scratch.backups().exists());
+    }
+
+    #[test]
+    fn overlay_paths_stay_below_the_content_folders() {
+        assert!(is_overlay_path(Path::new("content/sky/sky512_up.tex")));
+        assert!(is_overlay_path(Path::new("Content/fonts/arial.ttf")));
+        assert!(is_overlay_path(Path::new(
+            "PlatformContent/pc/textures/sky/sky512_up.tex"
+        )));
+        assert!(!is_overlay_path(Path::new("content")));
+        assert!(!is_overlay_path(Path::new("RobloxPlayerBeta.exe")));
+        assert!(!is_overlay_path(Path::new("shaders/shaders_d3d11.pack")));
+        assert!(!is_overlay_path(Path::new(
+            "content/../RobloxPlayerBeta.exe"
+        )));
+        assert!(!is_overlay_path(Path::new("/content/sky/sky512_up.tex")));
+    }
+
+    #[test]
+    fn overlay_keys_ignore_case() {
+        assert_eq!(
+            overlay_key(Path::new("Content/Sky/Sky512_Up.tex")),
+            overlay_key(Path::new("content/sky/sky512_up.tex"))
+        );
+    }
+}
@@ -0,0 +1,220 @@
+use std::collections::HashMap;
+use std::fs;
+use tauri::ipc::Channel;
+
+use crate::dedup;
+use crate::settings;
+use crate::tasks;
+
+const KNOWN_ROOTS: &str = include_str!("../KnownRoots.json");
+
+#[derive(serde::Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct KnownRoot {
+    extract_to: String,
+    // "Player" and/or "Studio": which clients can run without the package
+    #[serde(default)]
+    optional_for: Vec<String>,
+}
+
+#[derive(Clone, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OptionalPackage {
+    name: String,
+    extract_to: String,
+    skipped: bool,
+    installed: bool,
+}
+
+fn client_name(binary_type: &str) -> &'static str {
+    if binary_type.contains("Studio") {
+        "Studio"
+    } else {
+        "Player"
+    }
+}
+
+// Packages KnownRoots.json marks as safe to leave out of `binary_type`, as
+// (zip name, extract dir)
+fn optional_packages(binary_type: &str) -> Vec<(String, String)> {
+    let roots: HashMap<String, KnownRoot> = serde_json::from_str(KNOWN_ROOTS).unwrap_or_default();
+    let client = client_name(binary_type);
+    let mut optional: Vec<(String, String)> = roots
+        .into_iter()
+        .filter(|(_, root)| root.optional_for.iter().any(|c| c == client))
+        .map(|(name, root)| (format!("{}.zip", name), root.extract_to))
+        .collect();
+    optional.sort();
+    optional
+}
+
+pub fn is_optional(binary_type: &str, pkg: &str) -> bool {
+    optional_packages(binary_type)
+        .iter()
+        .any(|(name, _)| name == pkg)
+}
+
+pub fn skipped_packages(app: &tauri::AppHandle, binary_type: &str) -> Vec<String> {
+    settings::load(app)
+        .skipped_packages
+        .get(binary_type)
+        .map(|pkgs| {
+            pkgs.iter()
+                .filter(|p| is_optional(binary_type, p))
+                .cloned()
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+#[derive(Clone, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PkgEntry {
+    pub name: String,
+    pub md5: String,
+    pub packed_size: u64,
+    pub size: u64,
+}
+
+// rbxPkgManifest.txt is "v0" followed by name, MD5, packed size and unpacked size per package
+pub fn parse_pkg_manifest(content: &str) -> Vec<PkgEntry> {
+    let lines: Vec<&str> = content.lines().map(|l| l.trim()).collect();
+    if lines.first() != Some(&"v0") {
+        return Vec::new();
+    }
+    lines[1..]
+        .chunks(4)
+        .filter_map(|chunk| match chunk {
+            [name, md5, packed, size] => Some(PkgEntry {
+                name: name.to_string(),
+                md5: md5.to_lowercase(),
+                packed_size: packed.parse().unwrap_or(0),
+                size: size.parse().unwrap_or(0),
+            }),
+            _ => None,
+        })
+        .collect()
+}
+
+fn listed_packages(version_path: &std::path::Path) -> Option<Vec<String>> {
+    let content = fs::read_to_string(version_path.join("rbxPkgManifest.txt")).ok()?;
+    let entries = parse_pkg_manifest(&content);
+    if entries.is_empty() {
+        return None;
+    }
+    Some(entries.into_iter().map(|e| e.name).collect())
+}
+
+#[tauri::command]
+pub fn get_optional_packages(app: tauri::AppHandle, binary_type: String) -> Vec<OptionalPackage> {
+    let skipped = skipped_packages(&app, &binary_type);
+    let version_path = crate::installed_version_path(&app, &binary_type);
+    let listed = version_path.as_deref().and_then(listed_packages);
+
+    optional_packages(&binary_type)
+        .into_iter()
+        .filter(|(name, _)| listed.as_ref().map(|l| l.contains(name)).unwrap_or(true))
+        .map(|(name, extract_to)| {
+            let installed = version_path
+                .as_ref()
+                .map(|p| {
+                    fs::read_dir(p.join(&extract_to))
+                        .map(|mut entries| entries.next().is_some())
+                        .unwrap_or(false)
+                })
+                .unwrap_or(false);
+            OptionalPackage {
+                skipped: skipped.contains(&name),
+                name,
+                extract_to,
+                installed,
+            }
+        })
+        .collect()
+}
+
+#[tauri::command]
+pub fn set_skipped_packages(
+    app: tauri::AppHandle,
+    binary_type: String,
+    packages: Vec<String>,
+) -> Result<(), String> {
+    if let Some(pkg) = packages.iter().find(|p| !is_optional(&binary_type, p)) {
+        return Err(format!("{} is required and cannot be skipped", pkg));
+    }
+
+    let mut current = settings::load(&app);
+    current.skipped_packages.insert(binary_type, packages);
+    settings::save(&app, &current)
+}
+
+#[tauri::command]
+pub async fn install_optional_packages(
+    app: tauri::AppHandle,
+    binary_type: String,
+    packages: Vec<String>,
+    on_progress: Channel<tasks::TaskEvent>,
+) -> Result<String, String> {
+    if let Some(pkg) = packages.iter().find(|p| !is_optional(&binary_type, p)) {
+        return Err(format!("{} is not an optional package", pkg));
+    }
+    let task = tasks::start(
+        &app,
+        "Installing packages",
+        tasks::install_key(&binary_type),
+        on_progress,
+    )?;
+    tauri::async_runtime::spawn_blocking(move || {
+        let res = (|| {
+            let version_path = crate::installed_version_path(&app, &binary_type)
+                .ok_or("No installed version to add packages to")?;
+            let version = version_path
+                .file_name()
+                .map(|n| n.to_string_lossy().to_string())
+                .ok_or("Invalid version path")?;
+
+            let client = reqwest::blocking::Client::builder()
+                .user_agent("Roblox/WinInet")
+                .build()
+                .map_err(|e| e.to_string())?;
+            let donors = dedup::DonorIndex::build(&app, &version_path);
+            let pkg_manifest =
+                fs::read_to_string(version_path.join("rbxPkgManifest.txt")).unwrap_or_default();
+            let fetcher = crate::PackageFetcher::new(
+                &app,
+                &client,
+                crate::cdn_url_prefix(&binary_type),
+                &version,
+                &pkg_manifest,
+                Some(task.cancel_token()),
+            );
+
+            for (idx, pkg) in packages.iter().enumerate() {
+                task.progress(
+                    format!("Installing {}...", pkg),
+                    ((idx * 100) / packages.len()) as u64,
+                );
+                crate::install_package(&fetcher, pkg, &version_path, &donors)?;
+            }
+
+            crate::metadata::mark_packages_installed(&version_path, &packages);
+
+            // Once added by hand the package should stay on future updates
+            let mut current = settings::load(&app);
+            if let Some(skipped) = current.skipped_packages.get_mut(&binary_type) {
+                skipped.retain(|p| !packages.contains(p));
+            }
+            settings::save(&app, &current)?;
+
+            Ok(format!(
+                "Installed {} package(s) into {}",
+                packages.len(),
+                version
+            ))
+        })();
+        task.finish(&res);
+        res
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
@@ -0,0 +1,196 @@
+use std::collections::HashMap;
+use std::fs;
+use std::path::PathBuf;
+use tauri::Manager;
+
+// Settings the backend needs to act on without the frontend being open.
+// Keys mirror the names used by ConfigManager in src/utils/config.ts.
+#[derive(Clone, serde::Serialize, serde::Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct Settings {
+    pub auto_update: bool,
+    // Optional packages left out of installs, keyed by binary type
+    pub skipped_packages: HashMap<String, Vec<String>>,
+    // Folder for rblx-versions; None keeps it in the app data directory
+    pub install_root: Option<String>,
+    // LAN package sharing: serve our verified packages, fetch from peers first,
+    // and addresses ("host:port") to try besides the ones found over mDNS
+    pub lan_serve: bool,
+    pub lan_fetch: bool,
+    pub lan_peers: Vec<String>,
+    pub lan_port: u16,
+    // "user" or "system": where Sober and Vinegar get installed when missing
+    pub flatpak_installation: String,
+    // Studio on Linux: Vinegar, or our own Wine prefix using wine_binary
+    // (None finds wine on PATH), DXVK and extra environment
+    pub studio_backend: StudioBackend,
+    pub wine_binary: Option<String>,
+    pub wine_dxvk: bool,
+    pub wine_env: HashMap<String, String>,
+    // Ids of the built-in mod presets applied on launch
+    pub mod_presets: Vec<String>,
+    // Largest texture dimension for the low-spec pack; None leaves them alone
+    pub low_spec_max_size: Option<u32>,
+}
+
+#[derive(Clone, Copy, PartialEq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum StudioBackend {
+    Vinegar,
+    Wine,
+}
+
+// Keys that have a command of their own to validate them
+const VALIDATED_KEYS: &[(&str, &str)] = &[
+    ("installRoot", "set_install_root"),
+    ("studioBackend", "set_wine_settings"),
+    ("wineBinary", "set_wine_settings"),
+    ("wineDxvk", "set_wine_settings"),
+    ("wineEnv", "set_wine_settings"),
+];
+
+impl Default for Settings {
+    fn default() -> Self {
+        Settings {
+            auto_update: true,
+            skipped_packages: HashMap::new(),
+            install_root: None,
+            lan_serve: false,
+            lan_fetch: false,
+            lan_peers: Vec::new(),
+            lan_port: 48820,
+            flatpak_installation: "user".to_string(),
+            studio_backend: StudioBackend::Vinegar,
+            wine_binary: None,
+            wine_dxvk: true,
+            wine_env: HashMap::new(),
+            mod_presets: Vec::new(),
+            low_spec_max_size: None,
+        }
+    }
+}
+
+fn settings_path(app: &tauri::AppHandle) -> Result<PathBuf, String> {
+    let data_dir = app.path().app_local_data_dir().map_err(|e| e.to_string())?;
+    Ok(data_dir.join("settings.json"))
+}
+
+// Keeps every field the file got right. A bad value only resets that field,
+// and the bool is false when anything had to be dropped.
+fn parse(content: &str) -> (Settings, bool) {
+    let Ok(serde_json::Value::Object(file)) = serde_json::from_str(content) else {
+        return (Settings::default(), false);
+    };
+    let mut merged = match serde_json::to_value(Settings::default()) {
+        Ok(serde_json::Value::Object(obj)) => obj,
+        _ => return (Settings::default(), false),
+    };
+    let mut clean = true;
+    for (key, value) in file {
+        let previous = merged.insert(key.clone(), value);
+        let fits =
+            serde_json::from_value::<Settings>(serde_json::Value::Object(merged.clone())).is_ok();
+        if !fits {
+            println!("[Settings] Ignoring invalid value for {}", key);
+            clean = false;
+            match previous {
+                Some(previous) => merged.insert(key, previous),
+                None => merged.remove(&key),
+            };
+        }
+    }
+    let settings = serde_json::from_value(serde_json::Value::Object(merged)).unwrap_or_default();
+    (settings, clean)
+}
+
+pub fn load(app: &tauri::AppHandle) -> Settings {
+    let Ok(path) = settings_path(app) else {
+        return Settings::default();
+    };
+    let Ok(content) = fs::read_to_string(&path) else {
+        return Settings::default();
+    };
+    let (settings, clean) = parse(&content);
+    if !clean {
+        // The next save rewrites the file, so keep what the user had first
+        let backup = path.with_extension("json.bak");
+        match fs::copy(&path, &backup) {
+            Ok(_) => println!(
+                "[Settings] {} had invalid values, saved a copy to {}",
+                path.display(),
+                backup.display()
+            ),
+            Err(e) => println!("[Settings] Failed to back up {}: {}", path.display(), e),
+        }
+    }
+    settings
+}
+
+pub fn save(app: &tauri::AppHandle, settings: &Settings) -> Result<(), String> {
+    let path = settings_path(app)?;
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+    }
+    let json = serde_json::to_string_pretty(settings).map_err(|e| e.to_string())?;
+    fs::write(path, json).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub fn get_settings(app: tauri::AppHandle) -> Settings {
+    load(&app)
+}
+
+#[tauri::command]
+pub fn update_settings(
+    app: tauri::AppHandle,
+    settings: serde_json::Value,
+) -> Result<Settings, String> {
+    let mut current = serde_json::to_value(load(&app)).map_err(|e| e.to_string())?;
+
+    if let (Some(obj), Some(new_obj)) = (current.as_object_mut(), settings.as_object()) {
+        if let Some((key, command)) = VALIDATED_KEYS
+            .iter()
+            .find(|(key, _)| new_obj.contains_key(*key))
+        {
+            return Err(format!("{} must be changed through {}", key, command));
+        }
+        for (k, v) in new_obj {
+            obj.insert(k.clone(), v.clone());
+        }
+    } else {
+        return Err("Invalid settings structure".to_string());
+    }
+
+    let merged: Settings = serde_json::from_value(current).map_err(|e| e.to_string())?;
+    save(&app, &merged)?;
+    Ok(merged)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn invalid_fields_fall_back_alone() {
+        let (settings, clean) =
+            parse(r#"{"autoUpdate": false, "lanPort": "not a port", "lanPeers": ["a:1"]}"#);
+        assert!(!clean);
+        assert!(!settings.auto_update);
+        assert_eq!(settings.lan_port, Settings::default().lan_port);
+        assert_eq!(settings.lan_peers, vec!["a:1".to_string()]);
+    }
+
+    #[test]
+    fn unreadable_file_is_reported() {
+        let (settings, clean) = parse("{ not json");
+        assert!(!clean);
+        assert!(settings.auto_update);
+    }
+
+    #[test]
+    fn valid_file_is_clean() {
+        let (settings, clean) = parse(r#"{"studioBackend": "wine", "someOldKey": 1}"#);
+        assert!(clean);
+        assert!(settings.studio_backend == StudioBackend::Wine);
+    }
+}
@@ -0,0 +1,334 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+#[cfg(target_os = "windows")]
+use tauri::Manager;
+
+pub const SKY_FACES: [&str; 6] = [
+    "sky512_bk.tex",
+    "sky512_dn.tex",
+    "sky512_ft.tex",
+    "sky512_lf.tex",
+    "sky512_rt.tex",
+    "sky512_up.tex",
+];
+
+// All six faces must be there; a partial set leaves a mismatched sky
+fn source_faces(skybox_path: &str) -> Result<Vec<PathBuf>, String> {
+    let dir = Path::new(skybox_path);
+    if !dir.is_dir() {
+        return Err(format!("Skybox folder {} does not exist", skybox_path));
+    }
+    let faces: Vec<PathBuf> = SKY_FACES.iter().map(|face| dir.join(face)).collect();
+    let missing: Vec<&str> = SKY_FACES
+        .iter()
+        .zip(&faces)
+        .filter(|(_, path)| !path.is_file())
+        .map(|(face, _)| *face)
+        .collect();
+    if missing.is_empty() {
+        Ok(faces)
+    } else {
+        Err(format!("Skybox folder is missing {}", missing.join(", ")))
+    }
+}
+
+// Writes every face next to its target first, then renames them into place.
+// Renaming replaces the directory entry, so hardlinked files shared with other
+// versions keep their content.
+#[cfg(any(target_os = "windows", target_os = "linux"))]
+fn install_faces(sources: &[PathBuf], sky_dir: &Path) -> Result<(), String> {
+    fs::create_dir_all(sky_dir).map_err(|e| e.to_string())?;
+    let staged: Vec<PathBuf> = SKY_FACES
+        .iter()
+        .map(|face| sky_dir.join(format!("{}.tmp", face)))
+        .collect();
+
+    let copied = sources
+        .iter()
+        .zip(&staged)
+        .try_for_each(|(source, tmp)| fs::copy(source, tmp).map(|_| ()));
+    if let Err(e) = copied {
+        for tmp in &staged {
+            let _ = fs::remove_file(tmp);
+        }
+        return Err(format!("Failed to write skybox: {}", e));
+    }
+
+    for (face, tmp) in SKY_FACES.iter().zip(&staged) {
+        fs::rename(tmp, sky_dir.join(face)).map_err(|e| e.to_string())?;
+    }
+    Ok(())
+}
+
+#[cfg(target_os = "windows")]
+fn backups_dir(app: &tauri::AppHandle) -> Result<PathBuf, String> {
+    let data_dir = app.path().app_local_data_dir().map_err(|e| e.to_string())?;
+    Ok(data_dir.join("skybox-backups"))
+}
+
+// Sky folders of every Player and Studio version we can find, keyed by the
+// version folder name
+#[cfg(target_os = "windows")]
+fn installed_sky_dirs(app: &tauri::AppHandle) -> Vec<(String, PathBuf)> {
+    let mut roots = Vec::new();
+    if let Ok(local_app_data) = std::env::var("LOCALAPPDATA") {
+        roots.push(
+            PathBuf::from(local_app_data)
+                .join("Roblox")
+                .join("Versions"),
+        );
+    }
+    if let Ok(program_files_x86) = std::env::var("ProgramFiles(x86)") {
+        roots.push(
+            PathBuf::from(program_files_x86)
+                .join("Roblox")
+                .join("Versions"),
+        );
+    }
+    if let Ok(versions_dir) = crate::storage::versions_dir(app) {
+        roots.push(versions_dir);
+    }
+
+    let mut dirs = Vec::new();
+    for root in roots {
+        let Ok(entries) = fs::read_dir(root) else {
+            continue;
+        };
+        for entry in entries.flatten() {
+            let path = entry.path();
+            if !path.join("RobloxPlayerBeta.exe").exists()
+                && !path.join("RobloxStudioBeta.exe").exists()
+            {
+                continue;
+            }
+            let version = entry.file_name().to_string_lossy().to_string();
+            dirs.push((
+                version,
+                path.join("PlatformContent")
+                    .join("pc")
+                    .join("textures")
+                    .join("sky"),
+            ));
+        }
+    }
+    dirs
+}
+
+// Copies Roblox's own faces once per version. The folder existing marks the
+// backup as taken, so a later apply never backs up our own textures.
+#[cfg(target_os = "windows")]
+fn backup_originals(backup: &Path, sky_dir: &Path) -> Result<(), String> {
+    if backup.exists() {
+        return Ok(());
+    }
+    fs::create_dir_all(backup).map_err(|e| e.to_string())?;
+    for face in SKY_FACES {
+        let original = sky_dir.join(face);
+        if original.is_file() {
+            if let Err(e) = fs::copy(&original, backup.join(face)) {
+                let _ = fs::remove_dir_all(backup);
+                return Err(format!("Failed to back up {}: {}", face, e));
+            }
+        }
+    }
+    Ok(())
+}
+
+#[cfg(target_os = "windows")]
+fn restore_originals(backup: &Path, sky_dir: &Path) -> Result<(), String> {
+    for face in SKY_FACES {
+        let saved = backup.join(face);
+        let target = sky_dir.join(face);
+        if saved.is_file() {
+            let tmp = sky_dir.join(format!("{}.tmp", face));
+            fs::copy(&saved, &tmp).map_err(|e| e.to_string())?;
+            fs::rename(&tmp, &target).map_err(|e| e.to_string())?;
+        } else if target.exists() {
+            fs::remove_file(&target).map_err(|e| e.to_string())?;
+        }
+    }
+    fs::remove_dir_all(backup).map_err(|e| e.to_string())
+}
+
+// Backups of versions that have since been removed are no use to anyone
+#[cfg(target_os = "windows")]
+fn prune_backups(backups: &Path, installed: &[(String, PathBuf)]) {
+    if let Ok(entries) = fs::read_dir(backups) {
+        for entry in entries.flatten() {
+            let name = entry.file_name().to_string_lossy().to_string();
+            if !installed.iter().any(|(version, _)| *version == name) {
+                let _ = fs::remove_dir_all(entry.path());
+            }
+        }
+    }
+}
+
+#[cfg(target_os = "windows")]
+fn apply_to_sky_dir(
+    backups: &Path,
+    version: &str,
+    sky_dir: &Path,
+    sources: &[PathBuf],
+) -> Result<(), String> {
+    let backup = backups.join(version);
+    backup_originals(&backup, sky_dir)?;
+    if let Err(e) = install_faces(sources, sky_dir) {
+        // Never leave a version with half of one sky and half of another
+        let _ = restore_originals(&backup, sky_dir);
+        return Err(format!("{}: {}", version, e));
+    }
+    Ok(())
+}
+
+// Used at launch so a freshly installed version gets the chosen sky too.
+// "default" is what older builds saved after a reset, and restores Roblox's sky.
+#[cfg(target_os = "windows")]
+pub fn apply_to_version(
+    app: &tauri::AppHandle,
+    version_dir: &Path,
+    skybox_path: &str,
+) -> Result<(), String> {
+    let version = version_dir
+        .file_name()
+        .ok_or("Invalid version folder")?
+        .to_string_lossy()
+        .to_string();
+    let sky_dir = version_dir
+        .join("PlatformContent")
+        .join("pc")
+        .join("textures")
+        .join("sky");
+    let backups = backups_dir(app)?;
+    if skybox_path.eq_ignore_ascii_case("default") {
+        let backup = backups.join(&version);
+        if backup.is_dir() {
+            restore_originals(&backup, &sky_dir)?;
+        }
+        return Ok(());
+    }
+    let sources = source_faces(skybox_path)?;
+    apply_to_sky_dir(&backups, &version, &sky_dir, &sources)
+}
+
+#[tauri::command]
+#[allow(unused_variables)]
+pub async fn apply_skybox(app: tauri::AppHandle, skybox_path: String) -> Result<String, String> {
+    tauri::async_runtime::spawn_blocking(move || {
+        let sources = source_faces(&skybox_path)?;
+
+        #[cfg(target_os = "windows")]
+        {
+            let installed = installed_sky_dirs(&app);
+            if installed.is_empty() {
+                return Ok("No Roblox installations found to apply the skybox to.".to_string());
+            }
+            let backups = backups_dir(&app)?;
+            prune_backups(&backups, &installed);
+
+            // One broken version shouldn't keep the sky from the others; each
+            // failure already rolled itself back, so report them all at the end
+            let failures: Vec<String> = installed
+                .iter()
+                .filter_map(|(version, sky_dir)| {
+                    apply_to_sky_dir(&backups, version, sky_dir, &sources).err()
+                })
+                .collect();
+            let applied = installed.len() - failures.len();
+            println!(
+                "[Skybox] Applied {} to {} of {} versions",
+                skybox_path,
+                applied,
+                installed.len()
+            );
+            if applied == 0 {
+                return Err(format!(
+                    "Failed to apply the skybox: {}",
+                    failures.join("; ")
+                ));
+            }
+            if failures.is_empty() {
+                Ok(format!("Applied skybox to {} installations", applied))
+            } else {
+                // Launching a failed version retries it through apply_to_version
+                Ok(format!(
+                    "Applied skybox to {} of {} installations. Failed: {}",
+                    applied,
+                    installed.len(),
+                    failures.join("; ")
+                ))
+            }
+        }
+        #[cfg(target_os = "linux")]
+        {
+            // Sober's overlay sits on top of its assets, so there is nothing to back up
+            install_faces(&sources, &crate::sober_overlay::sky_dir(&app))?;
+            println!("[Skybox] Applied {} to Sober", skybox_path);
+            Ok("Applied skybox to Sober".to_string())
+        }
+        #[cfg(not(any(target_os = "windows", target_os = "linux")))]
+        {
+            Err("Skybox changer is only implemented for Windows and Linux currently.".to_string())
+        }
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
+
+#[tauri::command]
+#[allow(unused_variables)]
+pub async fn reset_skybox(app: tauri::AppHandle) -> Result<String, String> {
+    tauri::async_runtime::spawn_blocking(move || {
+        #[cfg(target_os = "windows")]
+        {
+            let backups = backups_dir(&app)?;
+            let mut restored = 0;
+            for (version, sky_dir) in installed_sky_dirs(&app) {
+                let backup = backups.join(&version);
+                if backup.is_dir() {
+                    restore_originals(&backup, &sky_dir)
+                        .map_err(|e| format!("{}: {}", version, e))?;
+                    restored += 1;
+                }
+            }
+            println!("[Skybox] Restored the default sky in {} versions", restored);
+            Ok(format!(
+                "Restored the default skybox in {} installations",
+                restored
+            ))
+        }
+        #[cfg(target_os = "linux")]
+        {
+            crate::sober_overlay::clear_skybox(&app)?;
+            println!("[Skybox] Restored the default sky in Sober");
+            Ok("Restored the default skybox".to_string())
+        }
+        #[cfg(not(any(target_os = "windows", target_os = "linux")))]
+        {
+            Err("Skybox changer is only implemented for Windows and Linux currently.".to_string())
+        }
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
+
+// Sky folder the client currently loads, for previewing what is installed
+#[tauri::command]
+#[allow(unused_variables)]
+pub fn get_installed_skybox(app: tauri::AppHandle) -> Option<String> {
+    #[cfg(target_os = "windows")]
+    let sky_dir = crate::installed_version_path(&app, "WindowsPlayer").map(|version| {
+        version
+            .join("PlatformContent")
+            .join("pc")
+            .join("textures")
+            .join("sky")
+    });
+    #[cfg(target_os = "linux")]
+    let sky_dir = Some(crate::sober_overlay::sky_dir(&app));
+    #[cfg(not(any(target_os = "windows", target_os = "linux")))]
+    let sky_dir: Option<PathBuf> = None;
+
+    sky_dir
+        .filter(|dir| SKY_FACES.iter().any(|face| dir.join(face).is_file()))
+        .map(|dir| dir.to_string_lossy().to_string())
+}
@@ -0,0 +1,247 @@
+use image::imageops::{self, FilterType};
+use image::{Rgba, RgbaImage};
+use std::collections::HashMap;
+use std::f32::consts::PI;
+use std::fs;
+use std::path::{Path, PathBuf};
+use tauri::Manager;
+
+use crate::skybox::SKY_FACES;
+use crate::tex;
+
+const DEFAULT_FACE_SIZE: u32 = 1024;
+
+type Vec3 = [f32; 3];
+
+// Where each face looks from inside the cube, and which directions are right
+// and down in its image. Roblox's front face looks along -Z.
+const FACE_AXES: [(&str, Vec3, Vec3, Vec3); 6] = [
+    ("bk", [0.0, 0.0, 1.0], [-1.0, 0.0, 0.0], [0.0, -1.0, 0.0]),
+    ("dn", [0.0, -1.0, 0.0], [1.0, 0.0, 0.0], [0.0, 0.0, 1.0]),
+    ("ft", [0.0, 0.0, -1.0], [1.0, 0.0, 0.0], [0.0, -1.0, 0.0]),
+    ("lf", [-1.0, 0.0, 0.0], [0.0, 0.0, -1.0], [0.0, -1.0, 0.0]),
+    ("rt", [1.0, 0.0, 0.0], [0.0, 0.0, 1.0], [0.0, -1.0, 0.0]),
+    ("up", [0.0, 1.0, 0.0], [1.0, 0.0, 0.0], [0.0, 0.0, -1.0]),
+];
+
+#[derive(serde::Deserialize)]
+#[serde(tag = "kind", rename_all = "camelCase")]
+pub enum SkyboxSource {
+    // One image path per face, keyed bk, dn, ft, lf, rt and up
+    Faces { faces: HashMap<String, String> },
+    // A folder of images named after their face, like sky512_bk.png or up.jpg
+    Folder { path: String },
+    // A single 2:1 equirectangular image split into the six faces
+    Panorama { path: String },
+}
+
+fn find_face_image(dir: &Path, face: &str) -> Option<String> {
+    fs::read_dir(dir).ok()?.flatten().find_map(|entry| {
+        let path = entry.path();
+        let ext = path.extension()?.to_string_lossy().to_lowercase();
+        let stem = path.file_stem()?.to_string_lossy().to_lowercase();
+        let is_image = matches!(ext.as_str(), "png" | "jpg" | "jpeg");
+        let is_face = stem == face || stem.ends_with(&format!("_{}", face));
+        (is_image && is_face).then(|| path.to_string_lossy().to_string())
+    })
+}
+
+fn load_image(path: &str) -> Result<RgbaImage, String> {
+    image::open(path)
+        .map(|img| img.to_rgba8())
+        .map_err(|e| format!("Could not read {}: {}", path, e))
+}
+
+fn sample_bilinear(image: &RgbaImage, x: f32, y: f32) -> Rgba<u8> {
+    let (width, height) = image.dimensions();
+    let x0 = x.floor();
+    let y0 = y.floor();
+    let (fx, fy) = (x - x0, y - y0);
+    // Longitude wraps around, latitude stops at the poles
+    let px = |x: f32| (x as i64).rem_euclid(width as i64) as u32;
+    let py = |y: f32| (y.max(0.0) as u32).min(height - 1);
+
+    let corners = [
+        image.get_pixel(px(x0), py(y0)),
+        image.get_pixel(px(x0 + 1.0), py(y0)),
+        image.get_pixel(px(x0), py(y0 + 1.0)),
+        image.get_pixel(px(x0 + 1.0), py(y0 + 1.0)),
+    ];
+    let weights = [
+        (1.0 - fx) * (1.0 - fy),
+        fx * (1.0 - fy),
+        (1.0 - fx) * fy,
+        fx * fy,
+    ];
+    let mut out = [0u8; 4];
+    for (channel, value) in out.iter_mut().enumerate() {
+        let sum: f32 = corners
+            .iter()
+            .zip(weights)
+            .map(|(c, w)| c[channel] as f32 * w)
+            .sum();
+        *value = sum.round().clamp(0.0, 255.0) as u8;
+    }
+    Rgba(out)
+}
+
+fn panorama_face(
+    panorama: &RgbaImage,
+    size: u32,
+    forward: Vec3,
+    right: Vec3,
+    down: Vec3,
+) -> RgbaImage {
+    let (width, height) = panorama.dimensions();
+    RgbaImage::from_fn(size, size, |x, y| {
+        let u = 2.0 * (x as f32 + 0.5) / size as f32 - 1.0;
+        let v = 2.0 * (y as f32 + 0.5) / size as f32 - 1.0;
+        let dir: Vec3 = [0, 1, 2].map(|i| forward[i] + u * right[i] + v * down[i]);
+        let length = (dir[0] * dir[0] + dir[1] * dir[1] + dir[2] * dir[2]).sqrt();
+
+        // Longitude 0 is the front face, in the middle of the panorama
+        let lon = dir[0].atan2(-dir[2]);
+        let lat = (dir[1] / length).asin();
+        let px = (0.5 + lon / (2.0 * PI)) * width as f32 - 0.5;
+        let py = (0.5 - lat / PI) * height as f32 - 0.5;
+        sample_bilinear(panorama, px, py)
+    })
+}
+
+fn face_images(source: &SkyboxSource, size: u32) -> Result<Vec<RgbaImage>, String> {
+    match source {
+        SkyboxSource::Faces { faces } => FACE_AXES
+            .iter()
+            .map(|(name, ..)| {
+                let path = faces
+                    .get(*name)
+                    .ok_or(format!("No image chosen for the {} face", name))?;
+                let image = load_image(path)?;
+                Ok(imageops::resize(&image, size, size, FilterType::Lanczos3))
+            })
+            .collect(),
+        SkyboxSource::Folder { path } => {
+            let mut faces = HashMap::new();
+            for (name, ..) in FACE_AXES {
+                let image = find_face_image(Path::new(path), name)
+                    .ok_or(format!("No image for the {} face in {}", name, path))?;
+                faces.insert(name.to_string(), image);
+            }
+            face_images(&SkyboxSource::Faces { faces }, size)
+        }
+        SkyboxSource::Panorama { path } => {
+            let panorama = load_image(path)?;
+            let (width, height) = panorama.dimensions();
+            if width < 4 || height < 2 {
+                return Err("The panorama image is too small".to_string());
+            }
+            Ok(FACE_AXES
+                .iter()
+                .map(|(_, forward, right, down)| {
+                    panorama_face(&panorama, size, *forward, *right, *down)
+                })
+                .collect())
+        }
+    }
+}
+
+// Encodes the six faces into `output`, replacing whatever skybox was there
+pub fn convert(source: &SkyboxSource, size: u32, output: &Path) -> Result<(), String> {
+    if !size.is_power_of_two() || !(128..=2048).contains(&size) {
+        return Err(format!(
+            "Face size must be a power of two between 128 and 2048, not {}",
+            size
+        ));
+    }
+    let encoded: Vec<Vec<u8>> = face_images(source, size)?
+        .iter()
+        .map(|image| tex::encode(image, true))
+        .collect();
+
+    fs::create_dir_all(output).map_err(|e| e.to_string())?;
+    // FACE_AXES and SKY_FACES are both in bk, dn, ft, lf, rt, up order
+    for (face, data) in SKY_FACES.iter().zip(encoded) {
+        let tmp = output.join(format!("{}.tmp", face));
+        fs::write(&tmp, data).map_err(|e| e.to_string())?;
+        fs::rename(&tmp, output.join(face)).map_err(|e| e.to_string())?;
+    }
+    Ok(())
+}
+
+pub fn custom_skybox_dir(app: &tauri::AppHandle) -> Result<PathBuf, String> {
+    let data_dir = app.path().app_local_data_dir().map_err(|e| e.to_string())?;
+    Ok(data_dir.join("custom-skybox"))
+}
+
+// Builds the custom skybox from ordinary images and returns its folder,
+// ready to be passed to apply_skybox
+#[tauri::command]
+pub async fn create_skybox(
+    app: tauri::AppHandle,
+    source: SkyboxSource,
+    size: Option<u32>,
+) -> Result<String, String> {
+    tauri::async_runtime::spawn_blocking(move || {
+        let output = custom_skybox_dir(&app)?;
+        convert(&source, size.unwrap_or(DEFAULT_FACE_SIZE), &output)?;
+        println!("[Skybox] Created custom skybox in {}", output.display());
+        Ok(output.to_string_lossy().to_string())
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Encodes longitude as a point on a circle in red and green, so it wraps
+    // cleanly at the back, and latitude from top to bottom in blue
+    fn coordinate_panorama() -> RgbaImage {
+        let (width, height) = (360, 180);
+        RgbaImage::from_fn(width, height, |x, y| {
+            let lon = ((x as f32 + 0.5) / width as f32 - 0.5) * 2.0 * PI;
+            let lat = 0.5 - (y as f32 + 0.5) / height as f32;
+            Rgba([
+                (128.0 + 127.0 * lon.cos()).round() as u8,
+                (128.0 + 127.0 * lon.sin()).round() as u8,
+                ((lat + 0.5) * 255.0).round() as u8,
+                255,
+            ])
+        })
+    }
+
+    // (longitude, latitude) in degrees sampled at the centre of a face
+    fn centre_coordinates(face: &RgbaImage) -> (f32, f32) {
+        let pixel = face.get_pixel(face.width() / 2, face.height() / 2);
+        let lon = (pixel[1] as f32 - 128.0)
+            .atan2(pixel[0] as f32 - 128.0)
+            .to_degrees();
+        let lat = (pixel[2] as f32 / 255.0 - 0.5) * 180.0;
+        (lon, lat)
+    }
+
+    #[test]
+    fn panorama_faces_look_the_right_way() {
+        let panorama = coordinate_panorama();
+        // None where longitude is meaningless at the poles
+        let expected = [
+            ("bk", Some(180.0), 0.0),
+            ("dn", None, -90.0),
+            ("ft", Some(0.0), 0.0),
+            ("lf", Some(-90.0), 0.0),
+            ("rt", Some(90.0), 0.0),
+            ("up", None, 90.0),
+        ];
+        for ((face, forward, right, down), (name, lon, lat)) in FACE_AXES.iter().zip(expected) {
+            assert_eq!(*face, name);
+            let image = panorama_face(&panorama, 9, *forward, *right, *down);
+            let (got_lon, got_lat) = centre_coordinates(&image);
+            assert!((got_lat - lat).abs() < 2.0, "{} latitude {}", face, got_lat);
+            if let Some(lon) = lon {
+                let diff = (got_lon - lon + 540.0).rem_euclid(360.0) - 180.0;
+                assert!(diff.abs() < 2.0, "{} longitude {}", face, got_lon);
+            }
+        }
+    }
+}
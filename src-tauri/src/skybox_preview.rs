@@ -0,0 +1,153 @@
+use image::imageops::{self, FilterType};
+use image::{ImageFormat, RgbaImage};
+use std::fs;
+use std::io::Cursor;
+use std::path::{Path, PathBuf};
+use std::time::UNIX_EPOCH;
+use tauri::ipc::Response;
+use tauri::Manager;
+
+use crate::dedup;
+use crate::skybox::SKY_FACES;
+use crate::tex;
+
+const DEFAULT_FACE_SIZE: u32 = 128;
+const MAX_CACHED: usize = 64;
+
+// Cells of the 4x3 cross: up above front, down below it, and the sides in a row
+const NET_LAYOUT: [(&str, u32, u32); 6] = [
+    ("sky512_up.tex", 1, 0),
+    ("sky512_lf.tex", 0, 1),
+    ("sky512_ft.tex", 1, 1),
+    ("sky512_rt.tex", 2, 1),
+    ("sky512_bk.tex", 3, 1),
+    ("sky512_dn.tex", 1, 2),
+];
+
+fn cache_dir(app: &tauri::AppHandle) -> Result<PathBuf, String> {
+    let cache = app.path().app_cache_dir().map_err(|e| e.to_string())?;
+    Ok(cache.join("skybox-previews"))
+}
+
+// Changes whenever any source file is replaced, so stale previews are never served
+fn cache_key(kind: &str, size: u32, sources: &[PathBuf]) -> String {
+    let mut key = format!("{}:{}", kind, size);
+    for source in sources {
+        let (len, modified) = fs::metadata(source)
+            .map(|m| {
+                let modified = m
+                    .modified()
+                    .ok()
+                    .and_then(|t| t.duration_since(UNIX_EPOCH).ok())
+                    .map(|d| d.as_nanos())
+                    .unwrap_or(0);
+                (m.len(), modified)
+            })
+            .unwrap_or((0, 0));
+        key.push_str(&format!("|{}:{}:{}", source.display(), len, modified));
+    }
+    dedup::bytes_md5(key.as_bytes())
+}
+
+// Drops the least recently written previews beyond MAX_CACHED
+fn prune_cache(dir: &Path) {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return;
+    };
+    let mut files: Vec<_> = entries
+        .flatten()
+        .filter_map(|e| Some((e.metadata().ok()?.modified().ok()?, e.path())))
+        .collect();
+    if files.len() <= MAX_CACHED {
+        return;
+    }
+    files.sort();
+    for (_, path) in &files[..files.len() - MAX_CACHED] {
+        let _ = fs::remove_file(path);
+    }
+}
+
+fn cached_png(
+    app: &tauri::AppHandle,
+    key: String,
+    render: impl FnOnce() -> Result<RgbaImage, String>,
+) -> Result<Vec<u8>, String> {
+    let dir = cache_dir(app)?;
+    let path = dir.join(format!("{}.png", key));
+    if let Ok(png) = fs::read(&path) {
+        return Ok(png);
+    }
+
+    let mut png = Vec::new();
+    render()?
+        .write_to(&mut Cursor::new(&mut png), ImageFormat::Png)
+        .map_err(|e| e.to_string())?;
+    if fs::create_dir_all(&dir).is_ok() {
+        let _ = fs::write(&path, &png);
+        prune_cache(&dir);
+    }
+    Ok(png)
+}
+
+fn load_face(path: &Path, size: u32) -> Result<RgbaImage, String> {
+    let data = fs::read(path).map_err(|e| format!("{}: {}", path.display(), e))?;
+    let face = tex::decode(&data).map_err(|e| format!("{}: {}", path.display(), e))?;
+    Ok(imageops::resize(&face, size, size, FilterType::Triangle))
+}
+
+fn check_size(size: Option<u32>) -> Result<u32, String> {
+    match size.unwrap_or(DEFAULT_FACE_SIZE) {
+        size @ 16..=1024 => Ok(size),
+        size => Err(format!("Preview size {} is out of range", size)),
+    }
+}
+
+// PNG of a single .tex face
+#[tauri::command]
+pub async fn preview_skybox_face(
+    app: tauri::AppHandle,
+    path: String,
+    size: Option<u32>,
+) -> Result<Response, String> {
+    let size = check_size(size)?;
+    tauri::async_runtime::spawn_blocking(move || {
+        let path = PathBuf::from(path);
+        let key = cache_key("face", size, std::slice::from_ref(&path));
+        cached_png(&app, key, || load_face(&path, size)).map(Response::new)
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
+
+// Cube-net thumbnail of the six faces in a skybox folder. Missing faces are
+// left transparent so a partial folder still shows what it has.
+#[tauri::command]
+pub async fn preview_skybox(
+    app: tauri::AppHandle,
+    skybox_path: String,
+    size: Option<u32>,
+) -> Result<Response, String> {
+    let size = check_size(size)?;
+    tauri::async_runtime::spawn_blocking(move || {
+        let dir = PathBuf::from(&skybox_path);
+        if !dir.is_dir() {
+            return Err(format!("Skybox folder {} does not exist", skybox_path));
+        }
+        let sources: Vec<PathBuf> = SKY_FACES.iter().map(|face| dir.join(face)).collect();
+        let key = cache_key("net", size, &sources);
+        cached_png(&app, key, || {
+            let mut net = RgbaImage::new(size * 4, size * 3);
+            for (face, col, row) in NET_LAYOUT {
+                let path = dir.join(face);
+                if path.is_file() {
+                    let image = load_face(&path, size)?;
+                    imageops::replace(&mut net, &image, (col * size) as i64, (row * size) as i64);
+                }
+            }
+            Ok(net)
+        })
+        .map(Response::new)
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
@@ -0,0 +1,124 @@
+use std::fs;
+use std::path::PathBuf;
+
+use crate::linux_apps;
+
+#[derive(Clone, Copy, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum GraphicsOptimizationMode {
+    Quality,
+    Balanced,
+    Performance,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum TouchMode {
+    Off,
+    On,
+    FakeOff,
+}
+
+// Sober's config.json. Keys keep Sober's own snake_case names, and anything
+// this model does not know about is carried through untouched. Keys that are
+// not set stay out of the file so Sober falls back to its own defaults.
+#[derive(Clone, Default, serde::Serialize, serde::Deserialize)]
+#[serde(default)]
+pub struct SoberConfig {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub allow_gamepad_permission: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub bring_back_oof: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub close_on_leave: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub discord_rpc_enabled: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub discord_rpc_show_join_button: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub enable_gamemode: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub enable_hidpi: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub graphics_optimization_mode: Option<GraphicsOptimizationMode>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub server_location_indicator_enabled: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub touch_mode: Option<TouchMode>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub use_console_experience: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub use_libsecret: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub use_opengl: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub fflags: Option<serde_json::Map<String, serde_json::Value>>,
+    #[serde(flatten)]
+    pub other: serde_json::Map<String, serde_json::Value>,
+}
+
+impl SoberConfig {
+    // What we start from before Sober has written its own config
+    fn initial() -> Self {
+        SoberConfig {
+            discord_rpc_enabled: Some(false),
+            graphics_optimization_mode: Some(GraphicsOptimizationMode::Performance),
+            ..Default::default()
+        }
+    }
+}
+
+pub fn config_path(app: &tauri::AppHandle) -> PathBuf {
+    linux_apps::sober(app).config_dir.join("config.json")
+}
+
+// A missing file means Sober has not run yet, so its defaults apply
+pub fn load(app: &tauri::AppHandle) -> Result<SoberConfig, String> {
+    let path = config_path(app);
+    if !path.exists() {
+        return Ok(SoberConfig::initial());
+    }
+    let content = fs::read_to_string(&path).map_err(|e| e.to_string())?;
+    serde_json::from_str(&content).map_err(|e| format!("Invalid Sober config: {}", e))
+}
+
+pub fn save(app: &tauri::AppHandle, config: &SoberConfig) -> Result<(), String> {
+    let path = config_path(app);
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+    }
+    let json = serde_json::to_string_pretty(config).map_err(|e| e.to_string())?;
+    fs::write(path, json).map_err(|e| e.to_string())
+}
+
+// Merges `changes` over the current config. Enum values are checked while
+// deserializing, so nothing is written if any key is invalid.
+pub fn update(app: &tauri::AppHandle, changes: &serde_json::Value) -> Result<SoberConfig, String> {
+    let changes = changes
+        .as_object()
+        .ok_or("Sober settings must be a JSON object")?;
+    let mut current = serde_json::to_value(load(app)?).map_err(|e| e.to_string())?;
+    if let Some(obj) = current.as_object_mut() {
+        for (k, v) in changes {
+            obj.insert(k.clone(), v.clone());
+        }
+    }
+
+    let merged: SoberConfig =
+        serde_json::from_value(current).map_err(|e| format!("Invalid Sober setting: {}", e))?;
+    save(app, &merged)?;
+    Ok(merged)
+}
+
+#[tauri::command]
+pub fn get_sober_config(app: tauri::AppHandle) -> Result<SoberConfig, String> {
+    load(&app)
+}
+
+#[tauri::command]
+pub fn update_sober_config(
+    app: tauri::AppHandle,
+    config: serde_json::Value,
+) -> Result<SoberConfig, String> {
+    update(&app, &config)
+}
@@ -0,0 +1,52 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::linux_apps;
+use crate::skybox::SKY_FACES;
+
+// Sober loads files from asset_overlay in place of the same path under the
+// client's content folder, so nothing inside the Flatpak is touched.
+pub fn overlay_dir(app: &tauri::AppHandle) -> PathBuf {
+    linux_apps::sober(app).data_dir.join("asset_overlay")
+}
+
+pub fn sky_dir(app: &tauri::AppHandle) -> PathBuf {
+    overlay_dir(app)
+        .join("PlatformContent")
+        .join("pc")
+        .join("textures")
+        .join("sky")
+}
+
+// Removes `dir` and its now empty parents, stopping at `root`
+fn prune_empty_dirs(dir: &Path, root: &Path) {
+    let mut current = Some(dir);
+    while let Some(dir) = current {
+        if dir == root || fs::remove_dir(dir).is_err() {
+            break;
+        }
+        current = dir.parent();
+    }
+}
+
+pub fn write_sky_face(app: &tauri::AppHandle, filename: &str, data: &[u8]) -> Result<(), String> {
+    if !SKY_FACES.contains(&filename) {
+        return Err(format!("{} is not a skybox face", filename));
+    }
+    let dir = sky_dir(app);
+    fs::create_dir_all(&dir).map_err(|e| e.to_string())?;
+    fs::write(dir.join(filename), data).map_err(|e| e.to_string())
+}
+
+// Without overlay faces Sober falls back to Roblox's own sky
+pub fn clear_skybox(app: &tauri::AppHandle) -> Result<(), String> {
+    let dir = sky_dir(app);
+    for face in SKY_FACES {
+        let path = dir.join(face);
+        if path.exists() {
+            fs::remove_file(&path).map_err(|e| e.to_string())?;
+        }
+    }
+    prune_empty_dirs(&dir, &overlay_dir(app));
+    Ok(())
+}
@@ -0,0 +1,333 @@
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use tauri::ipc::Channel;
+use tauri::Manager;
+
+use crate::dedup;
+use crate::settings;
+use crate::tasks;
+
+// Folder holding rblx-versions, rblx-staging, the LAN package cache and the
+// Wine prefix. A configured root that is missing (unplugged drive, offline
+// share) is reported instead of silently falling back, so versions never end
+// up re-downloaded onto the system drive.
+pub fn install_root(app: &tauri::AppHandle) -> Result<PathBuf, String> {
+    match settings::load(app).install_root {
+        Some(root) if !root.is_empty() => {
+            let path = PathBuf::from(&root);
+            if path.is_dir() {
+                Ok(path)
+            } else {
+                Err(format!(
+                    "Install location {} is not available. Reconnect the drive or change the install location.",
+                    root
+                ))
+            }
+        }
+        _ => app.path().app_local_data_dir().map_err(|e| e.to_string()),
+    }
+}
+
+pub fn versions_dir(app: &tauri::AppHandle) -> Result<PathBuf, String> {
+    Ok(install_root(app)?.join("rblx-versions"))
+}
+
+// Staging lives next to the versions so promoting a prefetched version is a rename
+pub fn staging_dir(app: &tauri::AppHandle) -> Result<PathBuf, String> {
+    Ok(install_root(app)?.join("rblx-staging"))
+}
+
+fn default_root(app: &tauri::AppHandle) -> Result<PathBuf, String> {
+    app.path().app_local_data_dir().map_err(|e| e.to_string())
+}
+
+// Everything under the install root that follows it to a new location,
+// including the LAN package cache so peers can still be served after a move.
+// rblx-staging only caches prefetched versions and is dropped instead.
+const MOVED_DIRS: [&str; 3] = ["rblx-versions", "rblx-packages", "wine-prefix"];
+
+// One file or folder being moved, kept so a failure can be rolled back
+struct Move {
+    source: PathBuf,
+    dest: PathBuf,
+    // Verified copy waiting to take the place of `dest`; None once renamed
+    copy: Option<PathBuf>,
+}
+
+fn sibling(path: &Path, suffix: &str) -> PathBuf {
+    let mut name = path.as_os_str().to_owned();
+    name.push(suffix);
+    PathBuf::from(name)
+}
+
+fn remove_entry(path: &Path) {
+    match fs::symlink_metadata(path) {
+        Ok(meta) if meta.is_dir() => {
+            let _ = fs::remove_dir_all(path);
+        }
+        Ok(_) => {
+            let _ = fs::remove_file(path);
+        }
+        Err(_) => {}
+    }
+}
+
+// The Wine prefix links its drives with symlinks, which are recreated
+// rather than followed
+#[cfg(unix)]
+fn copy_symlink(source: &Path, dest: &Path) -> Result<(), String> {
+    let target = fs::read_link(source).map_err(|e| e.to_string())?;
+    std::os::unix::fs::symlink(target, dest).map_err(|e| format!("{}: {}", dest.display(), e))
+}
+
+#[cfg(not(unix))]
+fn copy_symlink(_source: &Path, _dest: &Path) -> Result<(), String> {
+    Ok(())
+}
+
+fn copy_entry(source: &Path, dest: &Path) -> Result<(), String> {
+    let meta = fs::symlink_metadata(source).map_err(|e| format!("{}: {}", source.display(), e))?;
+    if meta.file_type().is_symlink() {
+        return copy_symlink(source, dest);
+    }
+    if !meta.is_dir() {
+        return fs::copy(source, dest)
+            .map(|_| ())
+            .map_err(|e| format!("{}: {}", source.display(), e));
+    }
+    fs::create_dir_all(dest).map_err(|e| e.to_string())?;
+    for entry in fs::read_dir(source).map_err(|e| e.to_string())? {
+        let entry = entry.map_err(|e| e.to_string())?;
+        copy_entry(&entry.path(), &dest.join(entry.file_name()))?;
+    }
+    Ok(())
+}
+
+// Every file must arrive with the same size, and files listed in a
+// version's rbxManifest.txt must still match their MD5.
+fn verify_copy(source: &Path, dest: &Path) -> Result<(), String> {
+    let original = fs::symlink_metadata(source).map_err(|e| e.to_string())?;
+    if original.is_file() {
+        let copied = fs::metadata(dest).map_err(|e| e.to_string())?;
+        if original.len() != copied.len() {
+            return Err(format!("{} has the wrong size", source.display()));
+        }
+        return Ok(());
+    }
+
+    let hashes: HashMap<String, String> = dedup::read_rbx_manifest(&dest.join("rbxManifest.txt"))
+        .into_iter()
+        .map(|(file, hash)| (dedup::manifest_key(Path::new(&file)), hash))
+        .collect();
+
+    for relative in dedup::walk_files(source) {
+        let original = fs::metadata(source.join(&relative)).map_err(|e| e.to_string())?;
+        let copied = fs::metadata(dest.join(&relative))
+            .map_err(|_| format!("{} is missing", relative.display()))?;
+        if original.len() != copied.len() {
+            return Err(format!("{} has the wrong size", relative.display()));
+        }
+        if let Some(hash) = hashes.get(&dedup::manifest_key(&relative)) {
+            if dedup::file_md5(&dest.join(&relative)).as_ref() != Some(hash) {
+                return Err(format!("{} failed verification", relative.display()));
+            }
+        }
+    }
+    Ok(())
+}
+
+// Same volume: a rename is instant and cannot corrupt anything. Otherwise
+// the copy goes beside `dest` and is verified; `dest` itself is untouched.
+fn move_entry(source: &Path, dest: &Path) -> Result<Move, String> {
+    if fs::symlink_metadata(dest).is_err() && fs::rename(source, dest).is_ok() {
+        return Ok(Move {
+            source: source.to_path_buf(),
+            dest: dest.to_path_buf(),
+            copy: None,
+        });
+    }
+    let copy = sibling(dest, ".nullstrap-tmp");
+    remove_entry(&copy);
+    if let Err(e) = copy_entry(source, &copy).and_then(|_| verify_copy(source, &copy)) {
+        remove_entry(&copy);
+        return Err(e);
+    }
+    Ok(Move {
+        source: source.to_path_buf(),
+        dest: dest.to_path_buf(),
+        copy: Some(copy),
+    })
+}
+
+// Puts every renamed entry back and drops unused copies, so the old
+// location stays complete
+fn rollback(moves: &[Move]) {
+    for m in moves {
+        match &m.copy {
+            Some(copy) => remove_entry(copy),
+            None => {
+                let _ = fs::rename(&m.dest, &m.source);
+            }
+        }
+    }
+}
+
+// Swaps a verified copy into place, keeping whatever it replaced beside it
+// until the whole move has succeeded
+fn swap_in(m: &Move, copy: &Path) -> Result<(), String> {
+    let replaced = sibling(&m.dest, ".nullstrap-old");
+    remove_entry(&replaced);
+    let had_dest = fs::rename(&m.dest, &replaced).is_ok();
+    if let Err(e) = fs::rename(copy, &m.dest) {
+        if had_dest {
+            let _ = fs::rename(&replaced, &m.dest);
+        }
+        return Err(format!("{}: {}", m.dest.display(), e));
+    }
+    Ok(())
+}
+
+// Undoes swap_in, leaving the copy for rollback to remove
+fn swap_out(m: &Move, copy: &Path) {
+    if fs::rename(&m.dest, copy).is_ok() {
+        let _ = fs::rename(sibling(&m.dest, ".nullstrap-old"), &m.dest);
+    }
+}
+
+// Moves the contents of MOVED_DIRS from `old_root` to `new_root`. Nothing
+// is deleted in either place until every entry has arrived. Returns how
+// many versions were moved.
+fn migrate(task: &tasks::TaskHandle, old_root: &Path, new_root: &Path) -> Result<usize, String> {
+    let mut entries = Vec::new();
+    for dir in MOVED_DIRS {
+        let Ok(children) = fs::read_dir(old_root.join(dir)) else {
+            continue;
+        };
+        let new_dir = new_root.join(dir);
+        fs::create_dir_all(&new_dir)
+            .map_err(|e| format!("Cannot write to {}: {}", new_dir.display(), e))?;
+        for child in children.flatten() {
+            entries.push((child.path(), new_dir.join(child.file_name())));
+        }
+    }
+
+    let total = entries.len().max(1);
+    let mut moves: Vec<Move> = Vec::new();
+    for (idx, (source, dest)) in entries.iter().enumerate() {
+        let name = source.strip_prefix(old_root).unwrap_or(source).display();
+        task.progress(format!("Moving {}...", name), ((idx * 100) / total) as u64);
+        match task
+            .check_cancelled()
+            .and_then(|_| move_entry(source, dest))
+        {
+            Ok(m) => moves.push(m),
+            Err(e) => {
+                rollback(&moves);
+                return Err(format!("Failed to move {}: {}", name, e));
+            }
+        }
+    }
+
+    // Sources are only removed once every copy is in place, so a failed
+    // swap can still put the old location back together
+    for (idx, m) in moves.iter().enumerate() {
+        let Some(copy) = &m.copy else {
+            continue;
+        };
+        if let Err(e) = swap_in(m, copy) {
+            for done in &moves[..idx] {
+                if let Some(copy) = &done.copy {
+                    swap_out(done, copy);
+                }
+            }
+            rollback(&moves);
+            return Err(format!("Failed to move {}: {}", m.source.display(), e));
+        }
+    }
+    for m in moves.iter().filter(|m| m.copy.is_some()) {
+        remove_entry(&sibling(&m.dest, ".nullstrap-old"));
+        remove_entry(&m.source);
+    }
+    for dir in MOVED_DIRS {
+        let _ = fs::remove_dir(old_root.join(dir));
+    }
+    let old_versions = old_root.join("rblx-versions");
+    Ok(moves
+        .iter()
+        .filter(|m| m.source.starts_with(&old_versions))
+        .count())
+}
+
+#[tauri::command]
+pub fn get_install_root(app: tauri::AppHandle) -> Result<String, String> {
+    install_root(&app).map(|p| p.to_string_lossy().to_string())
+}
+
+#[tauri::command]
+pub async fn set_install_root(
+    app: tauri::AppHandle,
+    install_root: Option<String>,
+    on_progress: Channel<tasks::TaskEvent>,
+) -> Result<String, String> {
+    if crate::is_roblox_running() {
+        return Err("Close Roblox before changing the install location.".to_string());
+    }
+    let task = tasks::start_all(
+        &app,
+        "Moving installs",
+        &["install:player", "install:studio"],
+        on_progress,
+    )?;
+
+    crate::show_progress_window(&app);
+    let app_clone = app.clone();
+    let res = tauri::async_runtime::spawn_blocking(move || {
+        let res = (|| {
+            let new_root = match install_root.filter(|r| !r.is_empty()) {
+                Some(root) => PathBuf::from(root),
+                None => default_root(&app_clone)?,
+            };
+            if !new_root.is_dir() {
+                return Err(format!("{} does not exist", new_root.display()));
+            }
+
+            let moved = match self::install_root(&app_clone) {
+                Ok(old_root) if old_root == new_root => 0,
+                Ok(old_root) => migrate(&task, &old_root, &new_root)?,
+                // An unreachable old root has nothing we can move; start fresh
+                Err(_) => 0,
+            };
+            let new_dir = new_root.join("rblx-versions");
+            fs::create_dir_all(&new_dir)
+                .map_err(|e| format!("Cannot write to {}: {}", new_dir.display(), e))?;
+
+            // Prefetched versions are only a cache and must sit beside the versions
+            if let Ok(staging) = staging_dir(&app_clone) {
+                let _ = fs::remove_dir_all(staging);
+            }
+
+            let mut current = settings::load(&app_clone);
+            current.install_root = if new_root == default_root(&app_clone)? {
+                None
+            } else {
+                Some(new_root.to_string_lossy().to_string())
+            };
+            settings::save(&app_clone, &current)?;
+
+            Ok(format!(
+                "Moved {} version(s) to {}",
+                moved,
+                new_dir.display()
+            ))
+        })();
+        task.finish(&res);
+        res
+    })
+    .await
+    .map_err(|e| e.to_string())?;
+
+    crate::close_progress_window(&app);
+
+    res
+}
@@ -0,0 +1,268 @@
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{SystemTime, UNIX_EPOCH};
+use tauri::ipc::Channel;
+use tauri::{Emitter, Manager};
+
+// Error returned by anything that stopped because its task was cancelled
+pub const CANCELLED: &str = "Cancelled";
+
+#[derive(Clone, serde::Serialize)]
+#[serde(rename_all = "camelCase", tag = "event", content = "data")]
+pub enum TaskEvent {
+    #[serde(rename_all = "camelCase")]
+    Started {
+        task_id: u64,
+        kind: String,
+    },
+    Progress {
+        status: String,
+        percent: u64,
+    },
+    Finished,
+    Failed {
+        error: String,
+    },
+    Cancelled,
+}
+
+// What the progress window gets, so it can offer to cancel the task it shows
+#[derive(Clone, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+struct TaskProgress {
+    task_id: u64,
+    status: String,
+    percent: u64,
+}
+
+#[derive(Clone, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TaskSummary {
+    id: u64,
+    kind: String,
+    key: String,
+    status: String,
+    percent: u64,
+    started_at: u64,
+    cancelling: bool,
+}
+
+#[derive(Clone, Default)]
+pub struct CancelToken(Arc<AtomicBool>);
+
+impl CancelToken {
+    pub fn is_cancelled(&self) -> bool {
+        self.0.load(Ordering::Relaxed)
+    }
+
+    pub fn check(&self) -> Result<(), String> {
+        if self.is_cancelled() {
+            Err(CANCELLED.to_string())
+        } else {
+            Ok(())
+        }
+    }
+}
+
+struct TaskEntry {
+    summary: TaskSummary,
+    keys: Vec<String>,
+    cancel: CancelToken,
+}
+
+#[derive(Default)]
+pub struct TaskRegistry {
+    next_id: AtomicU64,
+    tasks: Mutex<HashMap<u64, TaskEntry>>,
+}
+
+// A running task. Dropping it takes the task out of the registry.
+pub struct TaskHandle {
+    app: tauri::AppHandle,
+    id: u64,
+    cancel: CancelToken,
+    channel: Channel<TaskEvent>,
+}
+
+// Registers a task. Tasks sharing a key (e.g. installing the same binary type)
+// cannot run at the same time.
+pub fn start(
+    app: &tauri::AppHandle,
+    kind: &str,
+    key: &str,
+    channel: Channel<TaskEvent>,
+) -> Result<TaskHandle, String> {
+    start_all(app, kind, &[key], channel)
+}
+
+// For tasks that touch several things at once, like moving every version
+pub fn start_all(
+    app: &tauri::AppHandle,
+    kind: &str,
+    keys: &[&str],
+    channel: Channel<TaskEvent>,
+) -> Result<TaskHandle, String> {
+    let registry = app.state::<TaskRegistry>();
+    let mut tasks = registry.tasks.lock().map_err(|e| e.to_string())?;
+    if let Some(existing) = tasks
+        .values()
+        .find(|t| t.keys.iter().any(|k| keys.contains(&k.as_str())))
+    {
+        return Err(format!(
+            "{} is already in progress (task {})",
+            existing.summary.kind, existing.summary.id
+        ));
+    }
+
+    let id = registry.next_id.fetch_add(1, Ordering::Relaxed) + 1;
+    let cancel = CancelToken::default();
+    tasks.insert(
+        id,
+        TaskEntry {
+            summary: TaskSummary {
+                id,
+                kind: kind.to_string(),
+                key: keys.join(","),
+                status: "Starting...".to_string(),
+                percent: 0,
+                started_at: SystemTime::now()
+                    .duration_since(UNIX_EPOCH)
+                    .map(|d| d.as_secs())
+                    .unwrap_or(0),
+                cancelling: false,
+            },
+            keys: keys.iter().map(|k| k.to_string()).collect(),
+            cancel: cancel.clone(),
+        },
+    );
+    println!("[Tasks] Started {} ({})", id, kind);
+
+    let _ = channel.send(TaskEvent::Started {
+        task_id: id,
+        kind: kind.to_string(),
+    });
+    Ok(TaskHandle {
+        app: app.clone(),
+        id,
+        cancel,
+        channel,
+    })
+}
+
+// Key for anything that writes a binary type's version folder
+pub fn install_key(binary_type: &str) -> &'static str {
+    if binary_type.contains("Studio") {
+        "install:studio"
+    } else {
+        "install:player"
+    }
+}
+
+// Key for staging a binary type's next version in the background. Kept apart
+// from the install key so a prefetch never blocks Play.
+pub fn prefetch_key(binary_type: &str) -> &'static str {
+    if binary_type.contains("Studio") {
+        "prefetch:studio"
+    } else {
+        "prefetch:player"
+    }
+}
+
+// Status and percent of the task holding `key`, if any
+fn running(app: &tauri::AppHandle, key: &str) -> Option<(String, u64)> {
+    let registry = app.state::<TaskRegistry>();
+    let tasks = registry.tasks.lock().ok()?;
+    tasks
+        .values()
+        .find(|t| t.keys.iter().any(|k| k == key))
+        .map(|t| (t.summary.status.clone(), t.summary.percent))
+}
+
+pub fn is_running(app: &tauri::AppHandle, key: &str) -> bool {
+    running(app, key).is_some()
+}
+
+// Blocks until no task holds `key`, showing its progress on `task` meanwhile
+pub fn wait_for(app: &tauri::AppHandle, key: &str, task: &TaskHandle) -> Result<(), String> {
+    while let Some((status, percent)) = running(app, key) {
+        task.check_cancelled()?;
+        task.progress(status, percent);
+        std::thread::sleep(std::time::Duration::from_millis(250));
+    }
+    Ok(())
+}
+
+impl TaskHandle {
+    pub fn progress(&self, status: impl Into<String>, percent: u64) {
+        let status = status.into();
+        if let Ok(mut tasks) = self.app.state::<TaskRegistry>().tasks.lock() {
+            if let Some(entry) = tasks.get_mut(&self.id) {
+                entry.summary.status = status.clone();
+                entry.summary.percent = percent;
+            }
+        }
+        let _ = self.channel.send(TaskEvent::Progress {
+            status: status.clone(),
+            percent,
+        });
+        let _ = self.app.emit_to(
+            "progress",
+            "progress-update",
+            TaskProgress {
+                task_id: self.id,
+                status,
+                percent,
+            },
+        );
+    }
+
+    pub fn cancel_token(&self) -> CancelToken {
+        self.cancel.clone()
+    }
+
+    pub fn check_cancelled(&self) -> Result<(), String> {
+        self.cancel.check()
+    }
+
+    pub fn finish<T>(self, result: &Result<T, String>) {
+        let event = match result {
+            Ok(_) => TaskEvent::Finished,
+            Err(_) if self.cancel.is_cancelled() => TaskEvent::Cancelled,
+            Err(e) => TaskEvent::Failed { error: e.clone() },
+        };
+        let _ = self.channel.send(event);
+    }
+}
+
+impl Drop for TaskHandle {
+    fn drop(&mut self) {
+        if let Ok(mut tasks) = self.app.state::<TaskRegistry>().tasks.lock() {
+            tasks.remove(&self.id);
+        }
+        println!("[Tasks] Ended {}", self.id);
+    }
+}
+
+#[tauri::command]
+pub fn list_tasks(app: tauri::AppHandle) -> Result<Vec<TaskSummary>, String> {
+    let registry = app.state::<TaskRegistry>();
+    let tasks = registry.tasks.lock().map_err(|e| e.to_string())?;
+    let mut summaries: Vec<TaskSummary> = tasks.values().map(|t| t.summary.clone()).collect();
+    summaries.sort_by_key(|t| t.id);
+    Ok(summaries)
+}
+
+#[tauri::command]
+pub fn cancel_task(app: tauri::AppHandle, task_id: u64) -> Result<(), String> {
+    let registry = app.state::<TaskRegistry>();
+    let mut tasks = registry.tasks.lock().map_err(|e| e.to_string())?;
+    let entry = tasks
+        .get_mut(&task_id)
+        .ok_or(format!("Task {} is not running", task_id))?;
+    entry.cancel.0.store(true, Ordering::Relaxed);
+    entry.summary.cancelling = true;
+    entry.summary.status = "Cancelling...".to_string();
+    println!("[Tasks] Cancelling {}", task_id);
+    Ok(())
+}
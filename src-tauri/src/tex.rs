@@ -0,0 +1,374 @@
+use image::imageops::{self, FilterType};
+use image::RgbaImage;
+
+// Roblox .tex textures are DDS files. Sky faces use DXT1 (BC1), usually with a
+// full mipmap chain like the ones Roblox ships.
+const DDS_MAGIC: &[u8; 4] = b"DDS ";
+const DDSD_CAPS: u32 = 0x1;
+const DDSD_HEIGHT: u32 = 0x2;
+const DDSD_WIDTH: u32 = 0x4;
+const DDSD_PIXELFORMAT: u32 = 0x1000;
+const DDSD_MIPMAPCOUNT: u32 = 0x20000;
+const DDSD_LINEARSIZE: u32 = 0x80000;
+const DDPF_FOURCC: u32 = 0x4;
+const DDSCAPS_COMPLEX: u32 = 0x8;
+const DDSCAPS_TEXTURE: u32 = 0x1000;
+const DDSCAPS_MIPMAP: u32 = 0x400000;
+
+fn to_565(c: [f32; 3]) -> u16 {
+    let r = (c[0].clamp(0.0, 255.0) * 31.0 / 255.0).round() as u16;
+    let g = (c[1].clamp(0.0, 255.0) * 63.0 / 255.0).round() as u16;
+    let b = (c[2].clamp(0.0, 255.0) * 31.0 / 255.0).round() as u16;
+    (r << 11) | (g << 5) | b
+}
+
+fn from_565(c: u16) -> [f32; 3] {
+    let r = ((c >> 11) & 0x1f) as f32;
+    let g = ((c >> 5) & 0x3f) as f32;
+    let b = (c & 0x1f) as f32;
+    [r * 255.0 / 31.0, g * 255.0 / 63.0, b * 255.0 / 31.0]
+}
+
+// DXT1 blocks with c0 <= c1 use three colours plus transparent black; the
+// colour half of a DXT5 block always uses four
+fn palette(c0: u16, c1: u16, four_colour: bool) -> [[f32; 3]; 4] {
+    let a = from_565(c0);
+    let b = from_565(c1);
+    let mix = |wa: f32, wb: f32| {
+        [
+            (a[0] * wa + b[0] * wb) / 3.0,
+            (a[1] * wa + b[1] * wb) / 3.0,
+            (a[2] * wa + b[2] * wb) / 3.0,
+        ]
+    };
+    if four_colour {
+        [a, b, mix(2.0, 1.0), mix(1.0, 2.0)]
+    } else {
+        [
+            a,
+            b,
+            [
+                (a[0] + b[0]) / 2.0,
+                (a[1] + b[1]) / 2.0,
+                (a[2] + b[2]) / 2.0,
+            ],
+            [0.0; 3],
+        ]
+    }
+}
+
+fn distance(a: [f32; 3], b: [f32; 3]) -> f32 {
+    (a[0] - b[0]).powi(2) + (a[1] - b[1]).powi(2) + (a[2] - b[2]).powi(2)
+}
+
+// Endpoints are the two pixels furthest apart along the block's colour range,
+// which is close enough for smooth sky gradients.
+fn encode_block(pixels: &[[f32; 3]; 16]) -> [u8; 8] {
+    let mut min = [255.0f32; 3];
+    let mut max = [0.0f32; 3];
+    for p in pixels {
+        for i in 0..3 {
+            min[i] = min[i].min(p[i]);
+            max[i] = max[i].max(p[i]);
+        }
+    }
+    let axis = [max[0] - min[0], max[1] - min[1], max[2] - min[2]];
+    let project = |p: &[f32; 3]| p[0] * axis[0] + p[1] * axis[1] + p[2] * axis[2];
+    let (mut lo, mut hi) = (pixels[0], pixels[0]);
+    for p in pixels {
+        if project(p) < project(&lo) {
+            lo = *p;
+        }
+        if project(p) > project(&hi) {
+            hi = *p;
+        }
+    }
+
+    let (mut c0, mut c1) = (to_565(hi), to_565(lo));
+    if c0 < c1 {
+        std::mem::swap(&mut c0, &mut c1);
+    }
+    let mut indices = 0u32;
+    if c0 != c1 {
+        let colors = palette(c0, c1, true);
+        for (i, p) in pixels.iter().enumerate() {
+            let best = (0..4)
+                .min_by(|&a, &b| distance(*p, colors[a]).total_cmp(&distance(*p, colors[b])))
+                .unwrap_or(0) as u32;
+            indices |= best << (i * 2);
+        }
+    }
+
+    let mut block = [0u8; 8];
+    block[0..2].copy_from_slice(&c0.to_le_bytes());
+    block[2..4].copy_from_slice(&c1.to_le_bytes());
+    block[4..8].copy_from_slice(&indices.to_le_bytes());
+    block
+}
+
+fn encode_bc1(image: &RgbaImage) -> Vec<u8> {
+    let (width, height) = image.dimensions();
+    let mut out = Vec::with_capacity((width.div_ceil(4) * height.div_ceil(4) * 8) as usize);
+    for by in (0..height).step_by(4) {
+        for bx in (0..width).step_by(4) {
+            let mut pixels = [[0.0f32; 3]; 16];
+            for (i, pixel) in pixels.iter_mut().enumerate() {
+                // Edge blocks of tiny mips repeat their last row/column
+                let x = (bx + i as u32 % 4).min(width - 1);
+                let y = (by + i as u32 / 4).min(height - 1);
+                let p = image.get_pixel(x, y);
+                *pixel = [p[0] as f32, p[1] as f32, p[2] as f32];
+            }
+            out.extend_from_slice(&encode_block(&pixels));
+        }
+    }
+    out
+}
+
+fn mip_count(size: u32) -> u32 {
+    32 - size.leading_zeros()
+}
+
+fn header(width: u32, height: u32, mips: u32) -> Vec<u8> {
+    let linear_size = width.div_ceil(4) * height.div_ceil(4) * 8;
+    let mut flags = DDSD_CAPS | DDSD_HEIGHT | DDSD_WIDTH | DDSD_PIXELFORMAT | DDSD_LINEARSIZE;
+    let mut caps = DDSCAPS_TEXTURE;
+    if mips > 1 {
+        flags |= DDSD_MIPMAPCOUNT;
+        caps |= DDSCAPS_COMPLEX | DDSCAPS_MIPMAP;
+    }
+
+    let mut fields = vec![124, flags, height, width, linear_size, 0, mips];
+    fields.extend([0u32; 11]);
+    fields.extend([32, DDPF_FOURCC, u32::from_le_bytes(*b"DXT1"), 0, 0, 0, 0, 0]);
+    fields.extend([caps, 0, 0, 0, 0]);
+
+    let mut out = DDS_MAGIC.to_vec();
+    for field in fields {
+        out.extend_from_slice(&field.to_le_bytes());
+    }
+    out
+}
+
+// Encodes a DXT1 .tex, optionally with every mip level down to 1x1
+pub fn encode(image: &RgbaImage, mipmaps: bool) -> Vec<u8> {
+    let (width, height) = image.dimensions();
+    let mips = if mipmaps {
+        mip_count(width.max(height))
+    } else {
+        1
+    };
+
+    let mut out = header(width, height, mips);
+    out.extend(encode_bc1(image));
+    let mut level = image.clone();
+    for _ in 1..mips {
+        let (w, h) = level.dimensions();
+        level = imageops::resize(&level, (w / 2).max(1), (h / 2).max(1), FilterType::Triangle);
+        out.extend(encode_bc1(&level));
+    }
+    out
+}
+
+fn read_u32(data: &[u8], offset: usize) -> u32 {
+    u32::from_le_bytes([
+        data[offset],
+        data[offset + 1],
+        data[offset + 2],
+        data[offset + 3],
+    ])
+}
+
+fn decode_bc1_block(block: &[u8], out: &mut [[u8; 4]; 16], always_four: bool) {
+    let c0 = u16::from_le_bytes([block[0], block[1]]);
+    let c1 = u16::from_le_bytes([block[2], block[3]]);
+    let four_colour = always_four || c0 > c1;
+    let colors = palette(c0, c1, four_colour);
+    let indices = read_u32(block, 4);
+    for (i, pixel) in out.iter_mut().enumerate() {
+        let index = ((indices >> (i * 2)) & 0x3) as usize;
+        let c = colors[index];
+        let alpha = if !four_colour && index == 3 { 0 } else { 255 };
+        *pixel = [c[0] as u8, c[1] as u8, c[2] as u8, alpha];
+    }
+}
+
+fn decode_bc3_alpha(block: &[u8], out: &mut [[u8; 4]; 16]) {
+    let a0 = block[0] as f32;
+    let a1 = block[1] as f32;
+    let bits = block[2..8]
+        .iter()
+        .rev()
+        .fold(0u64, |acc, b| (acc << 8) | *b as u64);
+    for (i, pixel) in out.iter_mut().enumerate() {
+        let index = ((bits >> (i * 3)) & 0x7) as f32;
+        pixel[3] = match index as u8 {
+            0 => a0,
+            1 => a1,
+            _ if a0 > a1 => ((8.0 - index) * a0 + (index - 1.0) * a1) / 7.0,
+            6 => 0.0,
+            7 => 255.0,
+            _ => ((6.0 - index) * a0 + (index - 1.0) * a1) / 5.0,
+        } as u8;
+    }
+}
+
+// Decodes the top mip level of a DXT1, DXT5 or 32-bit uncompressed .tex
+pub fn decode(data: &[u8]) -> Result<RgbaImage, String> {
+    if data.len() < 128 || &data[0..4] != DDS_MAGIC {
+        return Err("Not a DDS texture".to_string());
+    }
+    let height = read_u32(data, 12);
+    let width = read_u32(data, 16);
+    let pf_flags = read_u32(data, 80);
+    let fourcc = &data[84..88];
+    if width == 0 || height == 0 || width > 8192 || height > 8192 {
+        return Err(format!("Unsupported texture size {}x{}", width, height));
+    }
+    let pixels = &data[128..];
+    let mut image = RgbaImage::new(width, height);
+
+    if pf_flags & DDPF_FOURCC == 0 {
+        if read_u32(data, 88) != 32 {
+            return Err("Only 32-bit uncompressed textures are supported".to_string());
+        }
+        let masks = [92, 96, 100, 104].map(|offset| read_u32(data, offset));
+        let needed = (width * height * 4) as usize;
+        if pixels.len() < needed {
+            return Err("Texture data is truncated".to_string());
+        }
+        let channel = |value: u32, mask: u32| {
+            if mask == 0 {
+                255
+            } else {
+                ((value & mask) >> mask.trailing_zeros()) as u8
+            }
+        };
+        for (i, pixel) in image.pixels_mut().enumerate() {
+            let value = read_u32(pixels, i * 4);
+            pixel.0 = [
+                channel(value, masks[0]),
+                channel(value, masks[1]),
+                channel(value, masks[2]),
+                channel(value, masks[3]),
+            ];
+        }
+        return Ok(image);
+    }
+
+    let block_size = match fourcc {
+        b"DXT1" => 8,
+        b"DXT5" => 16,
+        other => {
+            return Err(format!(
+                "Unsupported texture format {}",
+                String::from_utf8_lossy(other)
+            ))
+        }
+    };
+    let (blocks_x, blocks_y) = (width.div_ceil(4), height.div_ceil(4));
+    if pixels.len() < (blocks_x * blocks_y) as usize * block_size {
+        return Err("Texture data is truncated".to_string());
+    }
+
+    let mut block = [[0u8; 4]; 16];
+    for (n, chunk) in pixels
+        .chunks_exact(block_size)
+        .take((blocks_x * blocks_y) as usize)
+        .enumerate()
+    {
+        if block_size == 16 {
+            decode_bc1_block(&chunk[8..16], &mut block, true);
+            decode_bc3_alpha(&chunk[0..8], &mut block);
+        } else {
+            decode_bc1_block(chunk, &mut block, false);
+        }
+        let (bx, by) = (n as u32 % blocks_x * 4, n as u32 / blocks_x * 4);
+        for (i, pixel) in block.iter().enumerate() {
+            let (x, y) = (bx + i as u32 % 4, by + i as u32 / 4);
+            if x < width && y < height {
+                image.put_pixel(x, y, image::Rgba(*pixel));
+            }
+        }
+    }
+    Ok(image)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use image::Rgba;
+
+    // Colours that survive the 5:6:5 round trip exactly
+    const BLOCK_COLOURS: [[u8; 4]; 4] = [
+        [255, 0, 0, 255],
+        [0, 255, 0, 255],
+        [0, 0, 255, 255],
+        [255, 255, 255, 255],
+    ];
+
+    fn quadrants() -> RgbaImage {
+        RgbaImage::from_fn(8, 8, |x, y| {
+            Rgba(BLOCK_COLOURS[(y / 4 * 2 + x / 4) as usize])
+        })
+    }
+
+    #[test]
+    fn header_describes_a_dxt1_texture() {
+        let data = encode(&RgbaImage::new(8, 4), false);
+        assert_eq!(&data[0..4], DDS_MAGIC);
+        assert_eq!((read_u32(&data, 12), read_u32(&data, 16)), (4, 8));
+        assert_eq!(read_u32(&data, 28), 1);
+        assert_eq!(&data[84..88], b"DXT1");
+        // 128 byte header, then 8 bytes per 4x4 block
+        assert_eq!(data.len(), 128 + 2 * 8);
+    }
+
+    #[test]
+    fn mipmaps_go_down_to_one_pixel() {
+        let data = encode(&RgbaImage::new(8, 8), true);
+        assert_eq!(read_u32(&data, 28), 4);
+        // Four blocks at 8x8, then one each for 4x4, 2x2 and 1x1
+        assert_eq!(data.len(), 128 + (4 + 1 + 1 + 1) * 8);
+    }
+
+    #[test]
+    fn solid_blocks_keep_their_colour() {
+        let block = encode_block(&[[255.0, 0.0, 0.0]; 16]);
+        assert_eq!(u16::from_le_bytes([block[0], block[1]]), 0xf800);
+        assert_eq!(u16::from_le_bytes([block[2], block[3]]), 0xf800);
+        assert_eq!(block[4..8], [0; 4]);
+    }
+
+    #[test]
+    fn round_trip_keeps_solid_blocks() {
+        let image = quadrants();
+        let decoded = decode(&encode(&image, false)).unwrap();
+        assert_eq!(decoded, image);
+    }
+
+    #[test]
+    fn round_trip_with_mipmaps_decodes_the_top_level() {
+        let image = quadrants();
+        assert_eq!(decode(&encode(&image, true)).unwrap(), image);
+    }
+
+    // Within a block the colours lie on a line, which DXT1 can follow
+    #[test]
+    fn gradient_stays_close() {
+        let image = RgbaImage::from_fn(16, 16, |x, _| Rgba([(x * 16) as u8, 64, 128, 255]));
+        let decoded = decode(&encode(&image, false)).unwrap();
+        for (original, decoded) in image.pixels().zip(decoded.pixels()) {
+            for channel in 0..3 {
+                let diff = (original[channel] as i32 - decoded[channel] as i32).abs();
+                assert!(diff <= 16, "{:?} decoded as {:?}", original, decoded);
+            }
+            assert_eq!(decoded[3], 255);
+        }
+    }
+
+    #[test]
+    fn rejects_other_files() {
+        assert!(decode(b"not a texture").is_err());
+    }
+}
@@ -0,0 +1,172 @@
+use std::fs;
+use std::path::Path;
+use std::time::Duration;
+use tauri::ipc::Channel;
+use tauri::{Emitter, Manager};
+
+use crate::settings;
+use crate::storage::{staging_dir, versions_dir};
+use crate::tasks;
+
+const PREFETCH_INTERVAL: Duration = Duration::from_secs(30 * 60);
+const PREFETCH_STARTUP_DELAY: Duration = Duration::from_secs(60);
+const STAGED_MARKER: &str = ".nullstrap-staged";
+
+#[cfg(target_os = "windows")]
+const PREFETCH_BINARY_TYPES: &[&str] = &["WindowsPlayer", "WindowsStudio64"];
+#[cfg(target_os = "macos")]
+const PREFETCH_BINARY_TYPES: &[&str] = &["MacPlayer"];
+#[cfg(not(any(target_os = "windows", target_os = "macos")))]
+const PREFETCH_BINARY_TYPES: &[&str] = &[];
+
+#[derive(Clone, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+struct PrefetchPayload {
+    binary_type: String,
+    version: String,
+}
+
+fn is_installed(version_path: &Path) -> bool {
+    [
+        "RobloxPlayerBeta.exe",
+        "RobloxStudioBeta.exe",
+        "RobloxPlayer.app",
+        "RobloxStudio.app",
+    ]
+    .iter()
+    .any(|exe| version_path.join(exe).exists())
+}
+
+// Moves a fully prefetched version from the staging area into place.
+// Returns false when nothing usable was staged so the caller downloads as usual.
+pub fn promote_staged_version(app: &tauri::AppHandle, version: &str, version_path: &Path) -> bool {
+    let staged_path = match staging_dir(app) {
+        Ok(dir) => dir.join(version),
+        Err(_) => return false,
+    };
+    let marker = staged_path.join(STAGED_MARKER);
+    if !marker.exists() {
+        return false;
+    }
+
+    if version_path.exists() {
+        let _ = fs::remove_dir_all(version_path);
+    }
+    if let Some(parent) = version_path.parent() {
+        let _ = fs::create_dir_all(parent);
+    }
+
+    if let Err(e) = fs::rename(&staged_path, version_path) {
+        println!("[Prefetch] Failed to promote {}: {}", version, e);
+        return false;
+    }
+    let _ = fs::remove_file(version_path.join(STAGED_MARKER));
+    true
+}
+
+fn prefetch_version(app: &tauri::AppHandle, binary_type: &str) -> Result<String, String> {
+    let version = crate::get_latest_version(binary_type)?;
+
+    let version_path = versions_dir(app)?.join(&version);
+    let staged_path = staging_dir(app)?.join(&version);
+
+    if is_installed(&version_path) {
+        if staged_path.exists() {
+            let _ = fs::remove_dir_all(&staged_path);
+        }
+        return Ok(version);
+    }
+    if staged_path.join(STAGED_MARKER).exists() {
+        return Ok(version);
+    }
+
+    // An install already running downloads the same files itself
+    if tasks::is_running(app, tasks::install_key(binary_type)) {
+        return Err("An install is in progress".to_string());
+    }
+    // Nobody listens, but it shows up in the task list and can be cancelled
+    // from there. Play waits for it instead of downloading the files again.
+    let task = tasks::start(
+        app,
+        &format!("Prefetching {}", binary_type),
+        tasks::prefetch_key(binary_type),
+        Channel::new(|_| Ok(())),
+    )?;
+    println!("[Prefetch] Staging {} {}", binary_type, version);
+    if staged_path.exists() {
+        let _ = fs::remove_dir_all(&staged_path);
+    }
+    fs::create_dir_all(&staged_path).map_err(|e| e.to_string())?;
+
+    let res = crate::fetch_version_files(app, &version, binary_type, &staged_path, Some(&task));
+    task.finish(&res);
+    if let Err(e) = res {
+        let _ = fs::remove_dir_all(&staged_path);
+        return Err(e);
+    }
+
+    if !is_installed(&staged_path) {
+        let _ = fs::remove_dir_all(&staged_path);
+        return Err(format!("Prefetched {} is incomplete", version));
+    }
+    fs::write(staged_path.join(STAGED_MARKER), &version).map_err(|e| e.to_string())?;
+
+    let _ = app.emit(
+        "update-prefetched",
+        PrefetchPayload {
+            binary_type: binary_type.to_string(),
+            version: version.clone(),
+        },
+    );
+    Ok(version)
+}
+
+fn run_prefetch_cycle(app: &tauri::AppHandle) {
+    if !settings::load(app).auto_update {
+        return;
+    }
+    // Stay idle while the user is playing or a foreground install is showing progress
+    if crate::is_roblox_running() || app.get_webview_window("progress").is_some() {
+        return;
+    }
+
+    let mut current_versions = Vec::new();
+    let mut all_ok = true;
+    for binary_type in PREFETCH_BINARY_TYPES {
+        match prefetch_version(app, binary_type) {
+            Ok(version) => current_versions.push(version),
+            Err(e) => {
+                all_ok = false;
+                println!("[Prefetch] {} failed: {}", binary_type, e);
+            }
+        }
+    }
+    if !all_ok {
+        return;
+    }
+
+    // Drop staged versions that have since been superseded
+    if let Ok(dir) = staging_dir(app) {
+        if let Ok(entries) = fs::read_dir(dir) {
+            for entry in entries.flatten() {
+                let name = entry.file_name().to_string_lossy().to_string();
+                if !current_versions.contains(&name) {
+                    let _ = fs::remove_dir_all(entry.path());
+                }
+            }
+        }
+    }
+}
+
+pub fn spawn_prefetcher(app: tauri::AppHandle) {
+    if PREFETCH_BINARY_TYPES.is_empty() {
+        return;
+    }
+    std::thread::spawn(move || {
+        std::thread::sleep(PREFETCH_STARTUP_DELAY);
+        loop {
+            run_prefetch_cycle(&app);
+            std::thread::sleep(PREFETCH_INTERVAL);
+        }
+    });
+}
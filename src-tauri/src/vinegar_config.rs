@@ -0,0 +1,194 @@
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::linux_apps;
+
+#[derive(Clone, Copy, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub enum Renderer {
+    D3D11,
+    D3D11FL10,
+    Vulkan,
+    OpenGL,
+}
+
+// The [studio] table. Vinegar's own snake_case keys are kept as they are,
+// and keys that are not set stay out of the file so Vinegar's defaults apply.
+#[derive(Clone, Default, serde::Serialize, serde::Deserialize)]
+#[serde(default)]
+pub struct StudioConfig {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub channel: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub launcher: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub renderer: Option<Renderer>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub dxvk: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub forced_version: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub env: Option<BTreeMap<String, String>>,
+    #[serde(flatten)]
+    pub other: toml::Table,
+}
+
+// Vinegar's config.toml. Keys this model does not know about, including
+// the top-level fflags table, are carried through untouched.
+#[derive(Clone, Default, serde::Serialize, serde::Deserialize)]
+#[serde(default)]
+pub struct VinegarConfig {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub wineroot: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub env: Option<BTreeMap<String, String>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub studio: Option<StudioConfig>,
+    #[serde(flatten)]
+    pub other: toml::Table,
+}
+
+pub fn config_path(app: &tauri::AppHandle) -> PathBuf {
+    linux_apps::vinegar(app).config_dir.join("config.toml")
+}
+
+// A missing file means Vinegar has not run yet, so its defaults apply
+pub fn load(app: &tauri::AppHandle) -> Result<VinegarConfig, String> {
+    let path = config_path(app);
+    if !path.exists() {
+        return Ok(VinegarConfig::default());
+    }
+    let content = fs::read_to_string(&path).map_err(|e| e.to_string())?;
+    toml::from_str(&content).map_err(|e| format!("Invalid Vinegar config: {}", e))
+}
+
+pub fn save(app: &tauri::AppHandle, config: &VinegarConfig) -> Result<(), String> {
+    let path = config_path(app);
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+    }
+    let content = toml::to_string(config).map_err(|e| e.to_string())?;
+    fs::write(path, content).map_err(|e| e.to_string())
+}
+
+fn check_env(env: Option<&BTreeMap<String, String>>) -> Result<(), String> {
+    for (key, value) in env.into_iter().flatten() {
+        let valid_key = !key.is_empty()
+            && !key.starts_with(|c: char| c.is_ascii_digit())
+            && key.chars().all(|c| c.is_ascii_alphanumeric() || c == '_');
+        if !valid_key || value.contains('\n') {
+            return Err(format!("Invalid environment variable {:?}", key));
+        }
+    }
+    Ok(())
+}
+
+// Checks what serde cannot: paths, channel names and env keys
+fn validate(config: &VinegarConfig) -> Result<(), String> {
+    if let Some(wineroot) = config.wineroot.as_deref().filter(|w| !w.is_empty()) {
+        let root = Path::new(wineroot);
+        if !root.join("bin/wine").is_file() && !root.join("bin/wine64").is_file() {
+            return Err(format!(
+                "{} is not a Wine installation (no bin/wine inside)",
+                wineroot
+            ));
+        }
+    }
+    check_env(config.env.as_ref())?;
+
+    let Some(studio) = &config.studio else {
+        return Ok(());
+    };
+    if let Some(channel) = &studio.channel {
+        if !channel
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-')
+        {
+            return Err(format!("Invalid Studio channel {:?}", channel));
+        }
+    }
+
+    if studio.launcher.as_ref().is_some_and(|l| l.contains('\n')) {
+        return Err("The launcher wrapper must be a single line".to_string());
+    }
+
+    if let Some(version) = &studio.forced_version {
+        if !version.is_empty() && !version.starts_with("version-") {
+            return Err(format!("Invalid forced Studio version {:?}", version));
+        }
+    }
+
+    check_env(studio.env.as_ref())
+}
+
+// TOML has no null, so null values are dropped, as the old settings code did
+fn strip_nulls(value: &serde_json::Value) -> serde_json::Value {
+    match value {
+        serde_json::Value::Object(map) => serde_json::Value::Object(
+            map.iter()
+                .filter(|(_, v)| !v.is_null())
+                .map(|(k, v)| (k.clone(), strip_nulls(v)))
+                .collect(),
+        ),
+        serde_json::Value::Array(items) => serde_json::Value::Array(
+            items
+                .iter()
+                .filter(|v| !v.is_null())
+                .map(strip_nulls)
+                .collect(),
+        ),
+        other => other.clone(),
+    }
+}
+
+// Tables are merged key by key, except env and fflags which are replaced
+// whole so that entries can be removed.
+fn merge(target: &mut toml::Table, changes: toml::Table) {
+    for (key, value) in changes {
+        match (target.get_mut(&key), value) {
+            (Some(toml::Value::Table(existing)), toml::Value::Table(incoming))
+                if key != "env" && key != "fflags" =>
+            {
+                merge(existing, incoming)
+            }
+            (_, value) => {
+                target.insert(key, value);
+            }
+        }
+    }
+}
+
+// Merges `changes` over the current config. Nothing is written unless the
+// merged result deserializes and passes validation.
+pub fn update(
+    app: &tauri::AppHandle,
+    changes: &serde_json::Value,
+) -> Result<VinegarConfig, String> {
+    let changes = match toml::Value::try_from(strip_nulls(changes)) {
+        Ok(toml::Value::Table(table)) => table,
+        Ok(_) => return Err("Vinegar settings must be a JSON object".to_string()),
+        Err(e) => return Err(format!("Invalid Vinegar setting: {}", e)),
+    };
+    let mut current = toml::Table::try_from(load(app)?).map_err(|e| e.to_string())?;
+    merge(&mut current, changes);
+
+    let merged: VinegarConfig = current
+        .try_into()
+        .map_err(|e| format!("Invalid Vinegar setting: {}", e))?;
+    validate(&merged)?;
+    save(app, &merged)?;
+    Ok(merged)
+}
+
+#[tauri::command]
+pub fn get_vinegar_config(app: tauri::AppHandle) -> Result<VinegarConfig, String> {
+    load(&app)
+}
+
+#[tauri::command]
+pub fn update_vinegar_config(
+    app: tauri::AppHandle,
+    config: serde_json::Value,
+) -> Result<VinegarConfig, String> {
+    update(&app, &config)
+}
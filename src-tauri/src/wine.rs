@@ -0,0 +1,301 @@
+use std::collections::HashMap;
+use std::fs;
+use std::io::Read;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+use tauri::Manager;
+
+use crate::settings;
+use crate::storage;
+use crate::tasks;
+
+const STUDIO_BINARY_TYPE: &str = "WindowsStudio64";
+const DXVK_VERSION: &str = "2.5.3";
+const DXVK_DLLS: &[&str] = &["d3d9", "d3d10core", "d3d11", "dxgi"];
+
+#[derive(Clone, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WineStatus {
+    wine: Option<String>,
+    version: Option<String>,
+    prefix: String,
+    prefix_ready: bool,
+    dxvk_version: Option<String>,
+}
+
+// The prefix is large, so it lives next to the versions on the install drive
+pub fn prefix_dir(app: &tauri::AppHandle) -> Result<PathBuf, String> {
+    Ok(storage::install_root(app)?.join("wine-prefix"))
+}
+
+fn flags_path(app: &tauri::AppHandle) -> Result<PathBuf, String> {
+    let data_dir = app.path().app_local_data_dir().map_err(|e| e.to_string())?;
+    Ok(data_dir.join("studio-flags.json"))
+}
+
+// The configured binary when set, otherwise wine (or wine64) from PATH
+pub fn find_wine(app: &tauri::AppHandle) -> Result<PathBuf, String> {
+    if let Some(binary) = settings::load(app).wine_binary.filter(|b| !b.is_empty()) {
+        let path = PathBuf::from(&binary);
+        return if path.is_file() {
+            Ok(path)
+        } else {
+            Err(format!(
+                "The configured Wine binary {} does not exist",
+                binary
+            ))
+        };
+    }
+
+    let dirs: Vec<PathBuf> = std::env::var_os("PATH")
+        .map(|path| std::env::split_paths(&path).collect())
+        .unwrap_or_default();
+    ["wine", "wine64"]
+        .iter()
+        .flat_map(|name| dirs.iter().map(move |dir| dir.join(name)))
+        .find(|candidate| candidate.is_file())
+        .ok_or(
+            "Wine was not found on PATH. Install Wine or set its location in settings.".to_string(),
+        )
+}
+
+fn wine_version(wine: &Path) -> Option<String> {
+    let output = Command::new(wine).arg("--version").output().ok()?;
+    output
+        .status
+        .success()
+        .then(|| String::from_utf8_lossy(&output.stdout).trim().to_string())
+}
+
+fn installed_dxvk(prefix: &Path) -> Option<String> {
+    fs::read_to_string(prefix.join("dxvk-version"))
+        .ok()
+        .map(|v| v.trim().to_string())
+}
+
+// DXVK stays installed in the prefix; turning it off just prefers Wine's
+// own builtin DLLs again.
+fn dll_overrides(dxvk: bool) -> String {
+    format!("{}={}", DXVK_DLLS.join(","), if dxvk { "n,b" } else { "b" })
+}
+
+fn wine_command(wine: &Path, prefix: &Path) -> Command {
+    let mut cmd = Command::new(wine);
+    cmd.env("WINEPREFIX", prefix).env("WINEDEBUG", "-all");
+    cmd
+}
+
+fn ensure_prefix(wine: &Path, prefix: &Path, task: &tasks::TaskHandle) -> Result<(), String> {
+    if prefix.join("system.reg").exists() {
+        return Ok(());
+    }
+    task.progress("Creating Wine prefix...", 0);
+    println!("[Wine] Creating prefix at {}", prefix.display());
+    fs::create_dir_all(prefix).map_err(|e| e.to_string())?;
+
+    // Skipping Mono and Gecko avoids their install prompts; Studio needs neither
+    let output = wine_command(wine, prefix)
+        .args(["wineboot", "--init"])
+        .env("WINEDLLOVERRIDES", "mscoree,mshtml=")
+        .output()
+        .map_err(|e| format!("Failed to run Wine: {}", e))?;
+    if !output.status.success() || !prefix.join("system.reg").exists() {
+        let _ = fs::remove_dir_all(prefix);
+        return Err(format!(
+            "Failed to create the Wine prefix: {}",
+            String::from_utf8_lossy(&output.stderr)
+                .lines()
+                .rfind(|l| !l.trim().is_empty())
+                .unwrap_or("no output")
+        ));
+    }
+    Ok(())
+}
+
+// Unpacks the 64-bit and 32-bit DLLs from the DXVK release tarball
+fn install_dxvk(prefix: &Path, task: &tasks::TaskHandle) -> Result<(), String> {
+    if installed_dxvk(prefix).as_deref() == Some(DXVK_VERSION) {
+        return Ok(());
+    }
+    task.progress(format!("Installing DXVK {}...", DXVK_VERSION), 0);
+
+    let url = format!(
+        "https://github.com/doitsujin/dxvk/releases/download/v{0}/dxvk-{0}.tar.gz",
+        DXVK_VERSION
+    );
+    let client = reqwest::blocking::Client::builder()
+        .user_agent("nullstrap")
+        .build()
+        .map_err(|e| e.to_string())?;
+    let res = client.get(&url).send().map_err(|e| e.to_string())?;
+    if !res.status().is_success() {
+        return Err(format!("Failed to download DXVK: Status {}", res.status()));
+    }
+    let mut bytes = Vec::new();
+    res.take(64 * 1024 * 1024)
+        .read_to_end(&mut bytes)
+        .map_err(|e| e.to_string())?;
+    task.check_cancelled()?;
+
+    let windows = prefix.join("drive_c").join("windows");
+    let mut archive = tar::Archive::new(flate2::read::GzDecoder::new(bytes.as_slice()));
+    for entry in archive.entries().map_err(|e| e.to_string())? {
+        let mut entry = entry.map_err(|e| e.to_string())?;
+        let path = entry.path().map_err(|e| e.to_string())?.to_path_buf();
+        let mut components = path.iter().skip(1);
+        let target_dir = match components.next().and_then(|c| c.to_str()) {
+            Some("x64") => "system32",
+            Some("x32") => "syswow64",
+            _ => continue,
+        };
+        let Some(name) = components.next().filter(|_| components.next().is_none()) else {
+            continue;
+        };
+        entry
+            .unpack(windows.join(target_dir).join(name))
+            .map_err(|e| format!("Failed to install {}: {}", path.display(), e))?;
+    }
+
+    fs::write(prefix.join("dxvk-version"), DXVK_VERSION).map_err(|e| e.to_string())?;
+    println!("[Wine] Installed DXVK {}", DXVK_VERSION);
+    Ok(())
+}
+
+// Flags saved for Studio are kept here so every new version gets them too
+pub fn save_flags(app: &tauri::AppHandle, flags_json: &str) -> Result<(), String> {
+    let path = flags_path(app)?;
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+    }
+    fs::write(path, flags_json).map_err(|e| e.to_string())?;
+
+    if let Some(version_dir) = crate::installed_version_path(app, STUDIO_BINARY_TYPE) {
+        apply_flags(app, &version_dir)?;
+    }
+    Ok(())
+}
+
+fn apply_flags(app: &tauri::AppHandle, version_dir: &Path) -> Result<(), String> {
+    let Ok(flags) = fs::read_to_string(flags_path(app)?) else {
+        return Ok(());
+    };
+    let client_settings = version_dir.join("ClientSettings");
+    fs::create_dir_all(&client_settings).map_err(|e| e.to_string())?;
+    fs::write(client_settings.join("ClientAppSettings.json"), flags).map_err(|e| e.to_string())
+}
+
+pub fn launch_studio(app: &tauri::AppHandle, task: &tasks::TaskHandle) -> Result<(), String> {
+    let config = settings::load(app);
+    let wine = find_wine(app)?;
+    let prefix = prefix_dir(app)?;
+
+    let version = crate::get_latest_version(STUDIO_BINARY_TYPE)?;
+    task.progress("Verifying installation...", 0);
+    let exe_path = crate::download_and_install(app, &version, STUDIO_BINARY_TYPE, task)?;
+    let version_dir = exe_path.parent().ok_or("Invalid Studio path")?;
+
+    let app_settings = version_dir.join("AppSettings.xml");
+    if !app_settings.exists() {
+        let _ = fs::write(
+            app_settings,
+            r#"<?xml version="1.0" encoding="UTF-8"?>
+<Settings>
+    <ContentFolder>content</ContentFolder>
+    <BaseUrl>http://www.roblox.com</BaseUrl>
+</Settings>"#,
+        );
+    }
+    apply_flags(app, version_dir)?;
+
+    ensure_prefix(&wine, &prefix, task)?;
+    task.check_cancelled()?;
+    if config.wine_dxvk {
+        install_dxvk(&prefix, task)?;
+    }
+
+    task.progress("Launching Studio...", 100);
+    println!(
+        "[Wine] Launching {} with {}",
+        exe_path.display(),
+        wine.display()
+    );
+    wine_command(&wine, &prefix)
+        .arg(&exe_path)
+        .current_dir(version_dir)
+        .env("WINEDLLOVERRIDES", dll_overrides(config.wine_dxvk))
+        .envs(&config.wine_env)
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .spawn()
+        .map_err(|e| format!("Failed to start Studio with Wine: {}", e))?;
+    Ok(())
+}
+
+// Settings for running Studio through Wine, checked before they are saved
+#[tauri::command]
+pub fn set_wine_settings(
+    app: tauri::AppHandle,
+    backend: settings::StudioBackend,
+    binary: Option<String>,
+    dxvk: bool,
+    env: HashMap<String, String>,
+) -> Result<(), String> {
+    let binary = binary
+        .map(|b| b.trim().to_string())
+        .filter(|b| !b.is_empty());
+    if let Some(binary) = &binary {
+        if !Path::new(binary).is_file() {
+            return Err(format!("{} is not a file", binary));
+        }
+    }
+    for (key, value) in &env {
+        let valid_key = !key.is_empty()
+            && !key.starts_with(|c: char| c.is_ascii_digit())
+            && key.chars().all(|c| c.is_ascii_alphanumeric() || c == '_');
+        if !valid_key || value.contains('\0') {
+            return Err(format!("Invalid environment variable {:?}", key));
+        }
+    }
+
+    let mut current = settings::load(&app);
+    current.studio_backend = backend;
+    current.wine_binary = binary;
+    current.wine_dxvk = dxvk;
+    current.wine_env = env;
+    settings::save(&app, &current)
+}
+
+#[tauri::command]
+pub async fn get_wine_status(app: tauri::AppHandle) -> Result<WineStatus, String> {
+    tauri::async_runtime::spawn_blocking(move || {
+        let prefix = prefix_dir(&app)?;
+        let wine = find_wine(&app).ok();
+        Ok(WineStatus {
+            version: wine.as_deref().and_then(wine_version),
+            wine: wine.map(|w| w.to_string_lossy().to_string()),
+            prefix: prefix.to_string_lossy().to_string(),
+            prefix_ready: prefix.join("system.reg").exists(),
+            dxvk_version: installed_dxvk(&prefix),
+        })
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
+
+// Deletes the prefix; the next Studio launch creates a fresh one
+#[tauri::command]
+pub async fn reset_wine_prefix(app: tauri::AppHandle) -> Result<(), String> {
+    if crate::is_roblox_running() {
+        return Err("Close Roblox Studio before resetting the Wine prefix".to_string());
+    }
+    tauri::async_runtime::spawn_blocking(move || {
+        let prefix = prefix_dir(&app)?;
+        if prefix.exists() {
+            fs::remove_dir_all(&prefix).map_err(|e| e.to_string())?;
+            println!("[Wine] Removed prefix {}", prefix.display());
+        }
+        Ok(())
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
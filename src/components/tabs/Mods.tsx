@@ -6,40 +6,66 @@ import { open } from '@tauri-apps/plugin-dialog';
 import { writeFile, mkdir, exists } from '@tauri-apps/plugin-fs';
 import { appLocalDataDir, join } from '@tauri-apps/api/path';
 import { type as getOsType } from '@tauri-apps/plugin-os';
+import { revealItemInDir } from '@tauri-apps/plugin-opener';
+import { createTaskChannel } from '../../utils/tasks';
 
-const SKYBOX_ASSETS = import.meta.glob('/src/assets/skyboxes/**/*. {tex,png,jpg}', {
+const SKYBOX_ASSETS = import.meta.glob('/src/assets/skyboxes/**/*.{tex,png,jpg}', {
     query: '?url',
     import: 'default',
     eager: true
 });
 
+interface ModPreset {
+    id: string;
+    name: string;
+    description: string;
+    enabled: boolean;
+    available: boolean;
+}
+
+interface ModPackage {
+    id: string;
+    name: string;
+    version: string;
+    author?: string;
+    description?: string;
+    enabled: boolean;
+    supported: boolean;
+    files: number;
+    conflicts: string[];
+}
+
 export default function Mods() {
     const [cleanerStatus, setCleanerStatus] = useState<string | null>(null);
+    const [modsStatus, setModsStatus] = useState<string | null>(null);
+    const [modPackages, setModPackages] = useState<ModPackage[]>([]);
+    const [customFont, setCustomFont] = useState<string | null>(null);
+    const [modPresets, setModPresets] = useState<ModPreset[]>([]);
+    const [lowSpecSize, setLowSpecSize] = useState<number | null>(null);
+    const [lowSpecBusy, setLowSpecBusy] = useState(false);
     const [status, setStatus] = useState<string | null>(null);
     const [loading, setLoading] = useState(false);
     const [osType, setOsType] = useState<string>('windows');
 
-    const [soberSettings, setSoberSettings] = useState<Record<string, any>>({
-        allow_gamepad_permission: false,
-        close_on_leave: true,
-        discord_rpc_enabled: false,
-        discord_rpc_show_join_button: false,
-        enable_gamemode: true,
-        enable_hidpi: false,
-        server_location_indicator_enabled: false,
-        touch_mode: "off",
-        use_console_experience: false,
-        use_libsecret: false,
-        use_opengl: false,
-        graphics_optimization_mode: "quality"
-    });
+    const [soberSettings, setSoberSettings] = useState<Record<string, any>>({});
 
     useEffect(() => {
         const getOs = async () => {
             const t = await getOsType();
             setOsType(t);
+            if (t === 'linux') {
+                invoke<Record<string, any>>('get_sober_config')
+                    .then(config => setSoberSettings(prev => ({ ...prev, ...config })))
+                    .catch(e => setStatus("Error loading Sober settings: " + e));
+            }
         };
         getOs();
+        invoke<ModPackage[]>('list_mod_packages')
+            .then(setModPackages)
+            .catch(e => setModsStatus('Error: ' + String(e)));
+        invoke<string | null>('get_custom_font').then(setCustomFont);
+        invoke<ModPreset[]>('list_mod_presets').then(setModPresets);
+        invoke<number | null>('get_low_spec_textures').then(setLowSpecSize);
     }, []);
 
     const updateSoberSetting = (key: string, value: any) => {
@@ -69,43 +95,80 @@ export default function Mods() {
          }
     };
 
-    const installPredefinedSkybox = async (name: string) => {
-        setLoading(true);
-        setStatus(`Preparing ${name} skybox...`);
+    const [previewUrl, setPreviewUrl] = useState<string | null>(null);
+    const [installedPreviewUrl, setInstalledPreviewUrl] = useState<string | null>(null);
+
+    // Copies a bundled skybox out of the app so the backend can read it
+    const writeBundledSkybox = async (name: string) => {
+        const keys = Object.keys(SKYBOX_ASSETS).filter(k => k.includes(`/skyboxes/${name}/`));
         
-        try {
-            const keys = Object.keys(SKYBOX_ASSETS).filter(k => k.includes(`/skyboxes/${name}/`));
-            
-            if (keys.length === 0) {
-                throw new Error(`No assets found for skybox: ${name}`);
-            }
+        if (keys.length === 0) {
+            throw new Error(`No assets found for skybox: ${name}`);
+        }
 
-            const appData = await appLocalDataDir();
-            const tempDir = await join(appData, 'temp_skybox');
-            
-            if (!await exists(tempDir)) {
-                await mkdir(tempDir, { recursive: true });
-            }
+        const appData = await appLocalDataDir();
+        const tempDir = await join(appData, 'temp_skybox', name);
+        
+        if (!await exists(tempDir)) {
+            await mkdir(tempDir, { recursive: true });
+        }
 
-            setStatus(`Downloading ${keys.length} assets...`);
+        for (const key of keys) {
+            const assetUrl = SKYBOX_ASSETS[key] as string;
+            const fileName = key.split('/').pop(); 
+            if (!fileName) continue;
 
-            for (const key of keys) {
-                const assetUrl = SKYBOX_ASSETS[key] as string;
-                const fileName = key.split('/').pop(); 
-                if (!fileName) continue;
+            const destPath = await join(tempDir, fileName);
+            if (await exists(destPath)) continue;
 
-                const response = await fetch(assetUrl);
-                const blob = await response.blob();
-                const arrayBuffer = await blob.arrayBuffer();
-                const uint8Array = new Uint8Array(arrayBuffer);
+            const response = await fetch(assetUrl);
+            const blob = await response.blob();
+            const arrayBuffer = await blob.arrayBuffer();
+            await writeFile(destPath, new Uint8Array(arrayBuffer));
+        }
+        return tempDir;
+    };
 
-                const destPath = await join(tempDir, fileName);
-                await writeFile(destPath, uint8Array);
-            }
+    const loadPreview = async (skyboxPath: string) => {
+        const png = await invoke<ArrayBuffer>('preview_skybox', { skyboxPath });
+        return URL.createObjectURL(new Blob([png], { type: 'image/png' }));
+    };
+
+    const refreshInstalledPreview = async () => {
+        try {
+            const installed = await invoke<string | null>('get_installed_skybox');
+            setInstalledPreviewUrl(installed ? await loadPreview(installed) : null);
+        } catch (e) {
+            console.warn('Failed to preview installed skybox:', e);
+            setInstalledPreviewUrl(null);
+        }
+    };
+
+    useEffect(() => {
+        refreshInstalledPreview();
+    }, []);
+
+    useEffect(() => {
+        setPreviewUrl(null);
+        if (selectedSkybox === 'custom' || selectedSkybox === 'panorama') return;
+        let cancelled = false;
+        writeBundledSkybox(selectedSkybox)
+            .then(loadPreview)
+            .then(url => { if (!cancelled) setPreviewUrl(url); })
+            .catch(e => console.warn('Failed to preview skybox:', e));
+        return () => { cancelled = true; };
+    }, [selectedSkybox]);
+
+    const installPredefinedSkybox = async (name: string) => {
+        setLoading(true);
+        setStatus(`Preparing ${name} skybox...`);
+        
+        try {
+            const tempDir = await writeBundledSkybox(name);
 
             setStatus('Installing to Roblox...');
-            localStorage.setItem('activeSkyboxPath', tempDir);
             const result = await invoke('apply_skybox', { skyboxPath: tempDir });
+            localStorage.setItem('activeSkyboxPath', tempDir);
             setStatus(result as string);
 
         } catch (e) {
@@ -113,6 +176,7 @@ export default function Mods() {
             setStatus('Error: ' + String(e));
         } finally {
             setLoading(false);
+            refreshInstalledPreview();
         }
     };
 
@@ -126,22 +190,167 @@ export default function Mods() {
             
             if (selected) {
                 setLoading(true);
-                const path = selected as string;
+                let path = selected as string;
+                // A folder of ordinary images is converted to .tex first
+                if (!await exists(await join(path, 'sky512_bk.tex'))) {
+                    setStatus(`Converting images in ${path}...`);
+                    path = await invoke<string>('create_skybox', { source: { kind: 'folder', path } });
+                }
                 setStatus(`Installing custom skybox from ${path}...`);
+                const result = await invoke('apply_skybox', { skyboxPath: path });
                 localStorage.setItem('activeSkyboxPath', path);
+                setStatus(result as string);
+            }
+        } catch (e) {
+            setStatus('Error: ' + String(e));
+        } finally {
+            setLoading(false);
+            refreshInstalledPreview();
+        }
+    };
+
+    const installPanoramaSkybox = async () => {
+        try {
+            const selected = await open({
+                multiple: false,
+                filters: [{ name: 'Images', extensions: ['png', 'jpg', 'jpeg'] }]
+            });
+
+            if (selected) {
+                setLoading(true);
+                setStatus('Splitting panorama into skybox faces...');
+                const path = await invoke<string>('create_skybox', {
+                    source: { kind: 'panorama', path: selected as string }
+                });
                 const result = await invoke('apply_skybox', { skyboxPath: path });
+                localStorage.setItem('activeSkyboxPath', path);
                 setStatus(result as string);
             }
         } catch (e) {
             setStatus('Error: ' + String(e));
         } finally {
             setLoading(false);
+            refreshInstalledPreview();
+        }
+    };
+
+    const restoreDefaultSkybox = async () => {
+        setLoading(true);
+        setStatus('Restoring the default skybox...');
+        try {
+            localStorage.removeItem('activeSkyboxPath');
+            const result = await invoke('reset_skybox');
+            setStatus(result as string);
+        } catch (e) {
+            setStatus('Error: ' + String(e));
+        } finally {
+            setLoading(false);
+            refreshInstalledPreview();
+        }
+    };
+
+    const openModificationsFolder = async () => {
+        try {
+            const dir = await invoke<string>('get_modifications_dir');
+            await revealItemInDir(dir);
+        } catch (e) {
+            setModsStatus('Error: ' + String(e));
+        }
+    };
+
+    const runModifications = async (command: 'apply_modifications' | 'revert_modifications') => {
+        try {
+            const result = await invoke<string>(command);
+            setModsStatus(result);
+        } catch (e) {
+            setModsStatus('Error: ' + String(e));
+        }
+    };
+
+    const updateModPackages = async (command: string, args: Record<string, unknown> = {}) => {
+        try {
+            setModPackages(await invoke<ModPackage[]>(command, args));
+        } catch (e) {
+            setModsStatus('Error: ' + String(e));
+        }
+    };
+
+    const installModPackage = async () => {
+        const selected = await open({
+            multiple: false,
+            filters: [{ name: 'Mod package', extensions: ['zip'] }],
+        });
+        if (selected) {
+            await updateModPackages('install_mod_package', { path: selected as string });
+        }
+    };
+
+    const moveModPackage = (index: number, offset: number) => {
+        const ids = modPackages.map(p => p.id);
+        const target = index + offset;
+        if (target < 0 || target >= ids.length) return;
+        [ids[index], ids[target]] = [ids[target], ids[index]];
+        updateModPackages('reorder_mod_packages', { ids });
+    };
+
+    const chooseCustomFont = async () => {
+        const selected = await open({
+            multiple: false,
+            filters: [{ name: 'Font', extensions: ['ttf', 'otf'] }],
+        });
+        if (!selected) return;
+        try {
+            setCustomFont(await invoke<string>('set_custom_font', { path: selected as string }));
+            await runModifications('apply_modifications');
+        } catch (e) {
+            setModsStatus('Error: ' + String(e));
         }
     };
 
+    const resetCustomFont = async () => {
+        try {
+            await invoke('reset_custom_font');
+            setCustomFont(null);
+            await runModifications('apply_modifications');
+        } catch (e) {
+            setModsStatus('Error: ' + String(e));
+        }
+    };
+
+    const toggleModPreset = async (id: string, enabled: boolean) => {
+        try {
+            setModPresets(await invoke<ModPreset[]>('set_mod_preset_enabled', { id, enabled }));
+            await runModifications('apply_modifications');
+        } catch (e) {
+            setModsStatus('Error: ' + String(e));
+        }
+    };
+
+    const changeLowSpecSize = async (maxSize: number | null) => {
+        setLowSpecBusy(true);
+        setModsStatus(maxSize ? 'Downsampling textures, this can take a while...' : 'Restoring textures...');
+        try {
+            const onProgress = createTaskChannel((event) => {
+                if (event.event === 'progress') setModsStatus(event.data.status);
+            });
+            setModsStatus(await invoke<string>('set_low_spec_textures', { maxSize, onProgress }));
+            setLowSpecSize(maxSize);
+        } catch (e) {
+            setModsStatus('Error: ' + String(e));
+        } finally {
+            setLowSpecBusy(false);
+        }
+    };
+
+    const packageName = (id: string) => modPackages.find(p => p.id === id)?.name ?? id;
+
     const handleApplySkybox = () => {
-        if (selectedSkybox === 'custom') {
+        if (selectedSkybox === 'default') {
+            restoreDefaultSkybox();
+        } else if (selectedSkybox === 'custom') {
             installCustomSkybox();
+        } else if (selectedSkybox === 'panorama') {
+            installPanoramaSkybox();
         } else {
             installPredefinedSkybox(selectedSkybox);
         }
@@ -179,6 +388,7 @@ export default function Mods() {
                                   <Option value="default">Default</Option>
                                   <Option value="alya">Alya (Anime)</Option>
                                   <Option value="custom">Custom Folder...</Option>
+                                  <Option value="panorama">Panorama Image...</Option>
                               </Select>
                               
                               <Button 
@@ -188,10 +398,27 @@ export default function Mods() {
                                 loading={loading}
                                 sx={{ backgroundColor: 'var(--splash-btn-bg)', color: 'var(--text-primary)' }}
                               >
-                                  {selectedSkybox === 'custom' ? 'Browse & Apply' : 'Apply'}
+                                  {selectedSkybox === 'custom' || selectedSkybox === 'panorama' ? 'Browse & Apply' : 'Apply'}
                               </Button>
                           </Stack>
 
+                          {(previewUrl || installedPreviewUrl) && (
+                              <Stack direction="row" spacing={2}>
+                                  {previewUrl && (
+                                      <Box sx={{ flex: 1 }}>
+                                          <Typography level="body-xs" sx={{ color: 'var(--text-primary)', mb: 0.5 }}>Selected</Typography>
+                                          <img src={previewUrl} alt="Selected skybox" style={{ width: '100%', imageRendering: 'auto' }} />
+                                      </Box>
+                                  )}
+                                  {installedPreviewUrl && (
+                                      <Box sx={{ flex: 1 }}>
+                                          <Typography level="body-xs" sx={{ color: 'var(--text-primary)', mb: 0.5 }}>Installed</Typography>
+                                          <img src={installedPreviewUrl} alt="Installed skybox" style={{ width: '100%', imageRendering: 'auto' }} />
+                                      </Box>
+                                  )}
+                              </Stack>
+                          )}
+
                           {status && (
                              <Alert color={status.startsWith('Error') ? 'danger' : 'success'} sx={{ mt: 1 }}>
                                  {status}
@@ -201,6 +428,151 @@ export default function Mods() {
                 </ListItem>
             </List>
 
+            <Divider className="content-divider" sx={{ mt: 4 }} />
+            <List sx={{ mt: 2 }}>
+                <ListItem>
+                     <ListItemContent sx={{ display: 'flex', flexDirection: 'column', gap: 1 }}>
+                          <Typography level="body-md" className="option-header" sx={{ color: 'var(--text-primary)' }}>Modifications</Typography>
+                          <Typography level="body-sm" sx={{ mb: 1, color: 'var(--text-primary)' }}>
+                              Files placed in the content, PlatformContent and ExtraContent folders replace Roblox's own every time it launches.
+                          </Typography>
+                          <Stack direction="row" spacing={2}>
+                              <Button 
+                                variant="outlined" 
+                                size="sm" 
+                                onClick={openModificationsFolder}
+                                sx={{ borderColor: 'var(--border-color)', color: 'var(--text-primary)' }}
+                              >
+                                Open Folder
+                              </Button>
+                              <Button 
+                                variant="outlined" 
+                                size="sm" 
+                                onClick={() => runModifications('apply_modifications')}
+                                sx={{ borderColor: 'var(--border-color)', color: 'var(--text-primary)' }}
+                              >
+                                Apply Now
+                              </Button>
+                              <Button 
+                                variant="outlined" 
+                                size="sm" 
+                                onClick={() => runModifications('revert_modifications')}
+                                sx={{ borderColor: 'var(--border-color)', color: 'var(--text-primary)' }}
+                              >
+                                Restore Originals
+                              </Button>
+                          </Stack>
+                          {modPresets.map(preset => (
+                              <Box key={preset.id} sx={{ display: 'flex', justifyContent: 'space-between', alignItems: 'center', mt: 1 }}>
+                                  <Box>
+                                      <Typography level="body-md" sx={{ color: 'var(--text-primary)' }}>{preset.name}</Typography>
+                                      <Typography level="body-xs" sx={{ opacity: 0.6, color: 'var(--text-primary)' }}>
+                                          {preset.available ? preset.description : 'Not included in this build'}
+                                      </Typography>
+                                  </Box>
+                                  <Switch 
+                                      checked={preset.enabled} 
+                                      disabled={!preset.available && !preset.enabled}
+                                      onChange={(e) => toggleModPreset(preset.id, e.target.checked)}
+                                  />
+                              </Box>
+                          ))}
+
+                          {osType !== 'linux' && (
+                              <Box sx={{ display: 'flex', justifyContent: 'space-between', alignItems: 'center', mt: 1 }}>
+                                  <Box>
+                                      <Typography level="body-md" sx={{ color: 'var(--text-primary)' }}>Low-Spec Textures</Typography>
+                                      <Typography level="body-xs" sx={{ opacity: 0.6, color: 'var(--text-primary)' }}>
+                                          Downsamples Roblox's textures for older hardware
+                                      </Typography>
+                                  </Box>
+                                  <Select 
+                                      value={lowSpecSize ?? 0} 
+                                      disabled={lowSpecBusy}
+                                      onChange={(_e, v) => changeLowSpecSize(v ? v as number : null)}
+                                      sx={{ minWidth: 100 }}
+                                  >
+                                      <Option value={0}>Off</Option>
+                                      <Option value={512}>512px</Option>
+                                      <Option value={256}>256px</Option>
+                                      <Option value={128}>128px</Option>
+                                  </Select>
+                              </Box>
+                          )}
+
+                          {osType !== 'linux' && (
+                              <Stack direction="row" justifyContent="space-between" alignItems="center" sx={{ mt: 2 }}>
+                                  <Typography level="body-sm" sx={{ color: 'var(--text-primary)' }}>
+                                      Font: {customFont ? customFont.split(/[\\/]/).pop() : 'Roblox default'}
+                                  </Typography>
+                                  <Stack direction="row" spacing={1}>
+                                      <Button 
+                                        variant="outlined" 
+                                        size="sm" 
+                                        onClick={chooseCustomFont}
+                                        sx={{ borderColor: 'var(--border-color)', color: 'var(--text-primary)' }}
+                                      >
+                                        Choose Font...
+                                      </Button>
+                                      {customFont && (
+                                          <Button 
+                                            variant="outlined" 
+                                            size="sm" 
+                                            onClick={resetCustomFont}
+                                            sx={{ borderColor: 'var(--border-color)', color: 'var(--text-primary)' }}
+                                          >
+                                            Reset Font
+                                          </Button>
+                                      )}
+                                  </Stack>
+                              </Stack>
+                          )}
+
+                          <Stack direction="row" justifyContent="space-between" alignItems="center" sx={{ mt: 2 }}>
+                              <Typography level="body-sm" sx={{ color: 'var(--text-primary)' }}>
+                                  Mod packages are applied top to bottom, so lower ones win when two replace the same file.
+                              </Typography>
+                              <Button 
+                                variant="outlined" 
+                                size="sm" 
+                                onClick={installModPackage}
+                                sx={{ borderColor: 'var(--border-color)', color: 'var(--text-primary)' }}
+                              >
+                                Install Package...
+                              </Button>
+                          </Stack>
+                          {modPackages.map((pkg, index) => (
+                              <Box key={pkg.id} sx={{ display: 'flex', alignItems: 'center', gap: 1 }}>
+                                  <Switch 
+                                      checked={pkg.enabled} 
+                                      onChange={(e) => updateModPackages('set_mod_package_enabled', { id: pkg.id, enabled: e.target.checked })}
+                                      variant="soft"
+                                  />
+                                  <Box sx={{ flex: 1 }}>
+                                      <Typography level="body-md" sx={{ color: 'var(--text-primary)' }}>
+                                          {pkg.name} <Typography level="body-xs" sx={{ opacity: 0.6 }}>{pkg.version}{pkg.author ? ` by ${pkg.author}` : ''} · {pkg.files} files</Typography>
+                                      </Typography>
+                                      {!pkg.supported && (
+                                          <Typography level="body-xs" color="warning">Not made for this platform</Typography>
+                                      )}
+                                      {pkg.conflicts.length > 0 && (
+                                          <Typography level="body-xs" color="warning">
+                                              Replaces the same files as {pkg.conflicts.map(packageName).join(', ')}
+                                          </Typography>
+                                      )}
+                                  </Box>
+                                  <Button size="sm" variant="plain" disabled={index === 0} onClick={() => moveModPackage(index, -1)}>↑</Button>
+                                  <Button size="sm" variant="plain" disabled={index === modPackages.length - 1} onClick={() => moveModPackage(index, 1)}>↓</Button>
+                                  <Button size="sm" variant="plain" color="danger" onClick={() => updateModPackages('uninstall_mod_package', { id: pkg.id })}>Remove</Button>
+                              </Box>
+                          ))}
+                          {modsStatus && (
+                             <Typography level="body-xs" sx={{ mt: 1, color: modsStatus.startsWith('Error') ? 'red' : 'green' }}>{modsStatus}</Typography>
+                          )}
+                     </ListItemContent>
+                </ListItem>
+            </List>
+
             <Divider className="content-divider" sx={{ mt: 4 }} />
             <List sx={{ mt: 2 }}>
                 <ListItem>
@@ -248,8 +620,12 @@ export default function Mods() {
                         variant="solid" 
                         onClick={() => {
                             setStatus("Saving Sober settings...");
-                            invoke('save_fast_flags', { flagsJson: JSON.stringify(soberSettings), mode: 'sober_main' })
-                                .then(() => setStatus("Saved Sober settings!"))
+                            const { fflags: _fflags, ...settings } = soberSettings;
+                            invoke<Record<string, any>>('update_sober_config', { config: settings })
+                                .then((saved) => {
+                                    setSoberSettings(saved);
+                                    setStatus("Saved Sober settings!");
+                                })
                                 .catch(e => setStatus("Error saving: " + e));
                         }}
                         sx={{ backgroundColor: 'var(--bg-titlebar)' }}
@@ -261,6 +637,7 @@ export default function Mods() {
                 <List sx={{ maxHeight: '400px', overflowY: 'auto' }}>
                      {Object.entries({
                         allow_gamepad_permission: { label: "Allow Gamepad", type: "boolean" },
+                        bring_back_oof: { label: "Bring Back OOF", type: "boolean" },
                         close_on_leave: { label: "Close on Leave", type: "boolean" },
                         discord_rpc_enabled: { label: "Discord RPC", type: "boolean" },
                         discord_rpc_show_join_button: { label: "Discord: Show Join Button", type: "boolean" },
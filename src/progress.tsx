@@ -2,21 +2,26 @@ import React, { useEffect, useState } from "react";
 import ReactDOM from "react-dom/client";
 import { getCurrentWindow } from "@tauri-apps/api/window";
 import { listen } from "@tauri-apps/api/event";
+import { cancelTask } from "./utils/tasks";
 import "./style.css";
 
 interface ProgressPayload {
   status: string;
   percent: number;
+  taskId?: number;
 }
 
 const App = () => {
   const [status, setStatus] = useState("Initializing...");
   const [percent, setPercent] = useState(0);
+  const [taskId, setTaskId] = useState<number | null>(null);
+  const [cancelling, setCancelling] = useState(false);
 
   useEffect(() => {
     const unlistenUpdate = listen<ProgressPayload>("progress-update", (event) => {
       setStatus(event.payload.status);
       setPercent(event.payload.percent);
+      if (event.payload.taskId !== undefined) setTaskId(event.payload.taskId);
     });
 
     const unlistenClose = listen("progress-close", () => {
@@ -45,8 +50,37 @@ const App = () => {
       }}
       data-tauri-drag-region
     >
-      <div style={{ marginBottom: "10px", fontSize: "14px", fontWeight: 600 }}>
-        {status}
+      <div
+        style={{
+          display: "flex",
+          justifyContent: "space-between",
+          alignItems: "center",
+          marginBottom: "10px",
+        }}
+      >
+        <div style={{ fontSize: "14px", fontWeight: 600 }}>
+          {cancelling ? "Cancelling..." : status}
+        </div>
+        {taskId !== null && (
+          <button
+            disabled={cancelling}
+            onClick={() => {
+              setCancelling(true);
+              cancelTask(taskId).catch(() => setCancelling(false));
+            }}
+            style={{
+              background: "none",
+              border: "1px solid var(--border-color)",
+              borderRadius: "4px",
+              color: "inherit",
+              fontSize: "12px",
+              padding: "2px 8px",
+              cursor: cancelling ? "default" : "pointer",
+            }}
+          >
+            Cancel
+          </button>
+        )}
       </div>
       <div
         style={{
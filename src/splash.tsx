@@ -5,8 +5,10 @@ import { theme as customTheme } from './theme';
 import Button from '@mui/joy/Button';
 import Stack from '@mui/joy/Stack';
 import { invoke } from '@tauri-apps/api/core';
+import { listen } from '@tauri-apps/api/event';
 import { platform } from '@tauri-apps/plugin-os';
 import { Titlebar } from './components/Titlebar';
+import { createTaskChannel } from './utils/tasks';
 import './style.css';
 
 function useThemeSync() {
@@ -206,7 +208,11 @@ export function LaunchRoblox() {
         console.debug('LaunchRoblox clicked');
         const flags = localStorage.getItem('fastFlags_roblox') || '{}';
         const skybox = localStorage.getItem('activeSkyboxPath') || '';
-        invoke('launch_roblox', { flagsJson: flags, skyboxPath: skybox }).catch(
+        invoke('launch_roblox', {
+          flagsJson: flags,
+          skyboxPath: skybox,
+          onProgress: createTaskChannel(),
+        }).catch(
           () => console.error('launch_roblox failed')
         );
       }}
@@ -255,7 +261,7 @@ export function LaunchStudio() {
       sx={renderButtonSx('108px')}
       onClick={() => {
         console.debug('LaunchStudio clicked');
-        invoke('launch_studio').catch((err) =>
+        invoke('launch_studio', { onProgress: createTaskChannel() }).catch((err) =>
           console.error('launch_studio failed:', err)
         );
       }}
@@ -265,6 +271,85 @@ export function LaunchStudio() {
   );
 }
 
+interface FlatpakSetup {
+  installed: boolean;
+  distro: string;
+  command: string | null;
+  canEscalate: boolean;
+  message: string;
+}
+
+// Shown when Sober/Vinegar need Flatpak and it is missing
+function FlatpakSetupNotice() {
+  const [setup, setSetup] = useState<FlatpakSetup | null>(null);
+  const [busy, setBusy] = useState(false);
+  const [error, setError] = useState<string | null>(null);
+
+  useEffect(() => {
+    const unlisten = listen<FlatpakSetup>('flatpak-setup-required', (event) => {
+      setError(null);
+      setSetup(event.payload);
+    });
+    return () => {
+      unlisten.then((f) => f());
+    };
+  }, []);
+
+  if (!setup || setup.installed) return null;
+
+  const install = () => {
+    setBusy(true);
+    setError(null);
+    invoke<FlatpakSetup>('install_flatpak')
+      .then((result) => setSetup(result))
+      .catch((err) => setError(String(err)))
+      .finally(() => setBusy(false));
+  };
+
+  return (
+    <div
+      style={{
+        marginTop: '8px',
+        maxWidth: '420px',
+        padding: '8px 12px',
+        borderRadius: '6px',
+        fontSize: '0.75rem',
+        background: 'rgba(0,0,0,0.55)',
+        color: '#fff',
+      }}
+    >
+      <div>{setup.message}</div>
+      {setup.command && (
+        <code
+          style={{ display: 'block', margin: '6px 0', userSelect: 'text', wordBreak: 'break-all' }}
+        >
+          {setup.command}
+        </code>
+      )}
+      {error && <div style={{ color: '#fe968d' }}>{error}</div>}
+      <Stack direction="row" spacing={0.5} sx={{ gap: '4px', marginTop: '4px' }}>
+        {setup.command && setup.canEscalate && (
+          <Button size="sm" variant="soft" loading={busy} onClick={install}>
+            Install Flatpak
+          </Button>
+        )}
+        {setup.command && (
+          <Button
+            size="sm"
+            variant="plain"
+            onClick={() => navigator.clipboard.writeText(setup.command ?? '')}
+          >
+            Copy command
+          </Button>
+        )}
+        <Button size="sm" variant="plain" onClick={() => setSetup(null)}>
+          Dismiss
+        </Button>
+      </Stack>
+    </div>
+  );
+}
+
 export function OpenSettings() {
   const handleClick = async () => {
     console.debug(
@@ -308,6 +393,7 @@ function SplashApp() {
             <OpenSettings />
             <LaunchStudio />
           </Stack>
+          <FlatpakSetupNotice />
         </Stack>
       </div>
     </CssVarsProvider>
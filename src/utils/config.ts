@@ -1,3 +1,8 @@
+import { invoke } from "@tauri-apps/api/core";
+
+// Keys the Rust side also needs to see (persisted in its settings.json)
+const BACKEND_KEYS = ['autoUpdate'];
+
 export class ConfigManager {
     private static instance: ConfigManager;
 
@@ -48,6 +53,12 @@ export class ConfigManager {
         } catch (e) {
             console.error(`Error writing config key ${key} (${storageKey}):`, e);
         }
+
+        if (BACKEND_KEYS.includes(key)) {
+            invoke('update_settings', { settings: { [key]: value } }).catch((e) =>
+                console.error(`Error syncing config key ${key} to backend:`, e)
+            );
+        }
     }
 
     private getDefault(key: string): any {
@@ -64,15 +75,7 @@ export class ConfigManager {
             discordRpcEnabled: false,
             cpuCoreLimit: 0,
             fastFlags: {},
-            fastFlagsStudio: {},
-            sober: {
-                allow_gamepad_permission: false,
-                bring_back_oof: false,
-                close_on_leave: true,
-                discord_rpc_enabled: false,
-                enable_gamemode: true,
-                graphics_optimization_mode: "performance"
-            }
+            fastFlagsStudio: {}
         };
         
         if (defaults[key] !== undefined) return defaults[key];
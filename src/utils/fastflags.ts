@@ -1,11 +1,4 @@
-import { join, homeDir } from "@tauri-apps/api/path";
 import { invoke } from "@tauri-apps/api/core";
-import {
-  exists,
-  mkdir,
-  readTextFile,
-  writeTextFile,
-} from "@tauri-apps/plugin-fs";
 import { platform } from "@tauri-apps/plugin-os";
 import { ConfigManager } from "./config";
 
@@ -32,42 +25,8 @@ export async function saveFastFlagsToDisk() {
   const flagsStudio = processFlags(configManager.get("fastFlagsStudio"));
 
   if (currentPlatform === "linux") {
-    const home = await homeDir();
-    const configPath = await join(
-      home,
-      ".var",
-      "app",
-      "org.vinegarhq.Sober",
-      "config",
-      "sober",
-      "config.json",
-    );
-
-    let config: any = {};
-    if (await exists(configPath)) {
-      try {
-        const content = await readTextFile(configPath);
-        config = JSON.parse(content);
-      } catch (e) {
-        console.warn("Failed to parse existing Sober config", e);
-      }
-    } else {
-      const configDir = await join(
-        home,
-        ".var",
-        "app",
-        "org.vinegarhq.Sober",
-        "config",
-        "sober",
-      );
-      await mkdir(configDir, { recursive: true });
-    }
-
-    const soberSettings = configManager.get("sober");
-    Object.assign(config, soberSettings || {});
-    config.fflags = flagsRoblox;
-
-    await writeTextFile(configPath, JSON.stringify(config, null, 2));
+    // The backend knows where Sober keeps its config (Flatpak, native or Nix)
+    await invoke("update_sober_config", { config: { fflags: flagsRoblox } });
 
   } else {
     try {
@@ -5,6 +5,7 @@ import { showNotification } from "./ui";
 import { saveFastFlagsToDisk } from "./fastflags";
 import { applySkyboxToDisk } from "./skybox";
 import { setActivity } from "./discordRpc";
+import { createTaskChannel } from "./tasks";
 
 const configManager = ConfigManager.getInstance();
 
@@ -16,6 +17,12 @@ export async function launchRoblox() {
       const showNotifications = configManager.get("showNotifications");
 
       if (currentPlatform === "linux") {
+        try {
+          await applySkyboxToDisk();
+        } catch (modError) {
+          console.warn("[Launcher] Skybox application failed, but continuing launch:", modError);
+        }
+
         if (showNotifications) showNotification("launching sober...");
         if (configManager.get("discordRpcEnabled")) {
           setActivity("Launching Roblox", "Preparing to play");
@@ -25,7 +32,9 @@ export async function launchRoblox() {
       }
 
       if (showNotifications) showNotification("verifying roblox...");
-      const exePath = await invoke<string>("ensure_roblox_installed");
+      const exePath = await invoke<string>("ensure_roblox_installed", {
+        onProgress: createTaskChannel(),
+      });
       
       console.log(`[Launcher] Roblox verified at: ${exePath}`);
 
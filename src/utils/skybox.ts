@@ -22,7 +22,6 @@ const skyboxTextures = import.meta.glob(
 export async function applySkyboxToDisk() {
   const currentPlatform = platform();
   console.log(`[Skybox] Applying skybox for ${currentPlatform}`);
-  if (currentPlatform === "linux") return;
 
   let selected = configManager.get("currentSkybox");
   if (!selected) selected = "default";
@@ -32,6 +31,17 @@ export async function applySkyboxToDisk() {
       selected = parts[parts.length - 1]; 
   }
 
+  // Sober keeps its own sky once the overlay faces are gone
+  if (currentPlatform === "linux" && selected.toLowerCase() === "default") {
+    try {
+      await invoke("reset_skybox");
+    } catch (e) {
+      console.error("Failed to reset skybox:", e);
+      showNotification("Failed to reset skybox: " + e);
+    }
+    return;
+  }
+
   const suffixes = ["bk", "dn", "ft", "lf", "rt", "up"];
 
   try {
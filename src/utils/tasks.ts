@@ -0,0 +1,36 @@
+import { Channel, invoke } from "@tauri-apps/api/core";
+
+export type TaskEvent =
+    | { event: "started"; data: { taskId: number; kind: string } }
+    | { event: "progress"; data: { status: string; percent: number } }
+    | { event: "finished" }
+    | { event: "failed"; data: { error: string } }
+    | { event: "cancelled" };
+
+export interface TaskSummary {
+    id: number;
+    kind: string;
+    key: string;
+    status: string;
+    percent: number;
+    startedAt: number;
+    cancelling: boolean;
+}
+
+// Channel to pass as `onProgress` to long running commands
+export function createTaskChannel(onEvent?: (event: TaskEvent) => void): Channel<TaskEvent> {
+    const channel = new Channel<TaskEvent>();
+    channel.onmessage = (event) => {
+        if (event.event === "failed") console.error("[Task] failed:", event.data.error);
+        onEvent?.(event);
+    };
+    return channel;
+}
+
+export function listTasks(): Promise<TaskSummary[]> {
+    return invoke<TaskSummary[]>("list_tasks");
+}
+
+export function cancelTask(taskId: number): Promise<void> {
+    return invoke("cancel_task", { taskId });
+}